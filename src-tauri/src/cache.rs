@@ -1,14 +1,73 @@
 use std::fs;
+use std::io::ErrorKind;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
 use crate::db::models::RepoInfo;
+use crate::error::AppError;
+use crate::git::clone;
+use crate::git::discovery::ScanRoot;
+use crate::health::{self, HealthRule};
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Directories to scan, each with its own depth, globs and symlink settings.
     #[serde(default)]
-    pub scan_roots: Vec<String>,
+    pub scan_roots: Vec<ScanRoot>,
+    /// Maximum number of repos processed at once by fetch_all/pull_all.
+    #[serde(default = "default_bulk_concurrency")]
+    pub bulk_concurrency: usize,
+    /// Maximum simultaneous network operations against a single remote host.
+    #[serde(default = "default_per_host_concurrency")]
+    pub per_host_concurrency: usize,
+    /// Rules evaluated over every repo's status; see `health::HealthRule`.
+    #[serde(default = "health::default_rules")]
+    pub health_rules: Vec<HealthRule>,
+    /// Keep repo status live with a filesystem watcher; restored on launch.
+    #[serde(default)]
+    pub watch: bool,
+    /// Where `clone_repo` puts checkouts; see `git::clone::destination`.
+    #[serde(default = "default_clone_layout")]
+    pub clone_layout: String,
+    /// Why config.json couldn't be read. Defaults are used in its place, and
+    /// `save_config` refuses to overwrite the file.
+    #[serde(skip)]
+    pub load_error: Option<String>,
+    /// Health rules that failed to parse and were skipped, one message each.
+    #[serde(skip)]
+    pub rule_errors: Vec<String>,
+    /// The skipped rules as written, kept so saving doesn't drop them.
+    #[serde(skip)]
+    invalid_rules: Vec<serde_json::Value>,
+}
+
+fn default_bulk_concurrency() -> usize {
+    8
+}
+
+fn default_per_host_concurrency() -> usize {
+    4
+}
+
+fn default_clone_layout() -> String {
+    clone::DEFAULT_LAYOUT.to_string()
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            scan_roots: Vec::new(),
+            bulk_concurrency: default_bulk_concurrency(),
+            per_host_concurrency: default_per_host_concurrency(),
+            health_rules: health::default_rules(),
+            watch: false,
+            clone_layout: default_clone_layout(),
+            load_error: None,
+            rule_errors: Vec::new(),
+            invalid_rules: Vec::new(),
+        }
+    }
 }
 
 fn data_dir() -> Option<PathBuf> {
@@ -21,41 +80,156 @@ fn ensure_dir() -> Option<PathBuf> {
     Some(dir)
 }
 
-// ── Repo cache ──
+// ── Database location ──
 
-pub fn save(repos: &[RepoInfo]) {
-    let Some(dir) = ensure_dir() else { return };
-    let path = dir.join("cache.json");
-    if let Ok(json) = serde_json::to_string(repos) {
-        let _ = fs::write(&path, json);
-    }
+pub fn database_path() -> Option<PathBuf> {
+    ensure_dir().map(|dir| dir.join("gitatlas.db"))
 }
 
-pub fn load() -> Vec<RepoInfo> {
-    let Some(dir) = data_dir() else {
-        return Vec::new();
-    };
-    let Ok(data) = fs::read_to_string(dir.join("cache.json")) else {
-        return Vec::new();
-    };
-    serde_json::from_str(&data).unwrap_or_default()
+// ── Legacy repo cache ──
+
+fn legacy_cache_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("cache.json"))
+}
+
+/// The repos in the pre-database `cache.json`, if one is still around and
+/// parses, so they can be imported into the on-disk database.
+pub fn read_legacy_repos() -> Option<Vec<RepoInfo>> {
+    let data = fs::read_to_string(legacy_cache_path()?).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Remove `cache.json` once its repos are safely in the database. If that
+/// fails the import simply repeats on the next launch.
+pub fn remove_legacy_repos() {
+    if let Some(path) = legacy_cache_path() {
+        let _ = fs::remove_file(path);
+    }
 }
 
 // ── Config ──
 
+/// Read config.json. A missing file gives the defaults; an unreadable or
+/// malformed one gives the defaults with `load_error` set. A bad health rule
+/// only skips that rule.
 pub fn load_config() -> Config {
     let Some(dir) = data_dir() else {
         return Config::default();
     };
-    let Ok(data) = fs::read_to_string(dir.join("config.json")) else {
-        return Config::default();
+    let data = match fs::read_to_string(dir.join("config.json")) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Config::default(),
+        Err(e) => return failed_load(e.to_string()),
+    };
+    parse_config(&data).unwrap_or_else(failed_load)
+}
+
+fn failed_load(error: String) -> Config {
+    Config {
+        load_error: Some(format!("config.json: {}", error)),
+        ..Config::default()
+    }
+}
+
+fn parse_config(data: &str) -> Result<Config, String> {
+    let mut value: serde_json::Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    // Rules are parsed one at a time so a typo in one doesn't cost the rest
+    let rules = value.as_object_mut().and_then(|o| o.remove("health_rules"));
+    let mut config: Config = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    let Some(rules) = rules else {
+        return Ok(config);
+    };
+    let serde_json::Value::Array(rules) = rules else {
+        return Err("health_rules must be a list".to_string());
+    };
+
+    config.health_rules = Vec::new();
+    for (index, rule) in rules.into_iter().enumerate() {
+        match HealthRule::deserialize(&rule) {
+            Ok(parsed) => config.health_rules.push(parsed),
+            Err(e) => {
+                let id = rule.get("id").and_then(|id| id.as_str()).unwrap_or("?");
+                config
+                    .rule_errors
+                    .push(format!("Health rule {} ('{}') skipped: {}", index + 1, id, e));
+                config.invalid_rules.push(rule);
+            }
+        }
+    }
+    Ok(config)
+}
+
+/// Write `config` back to config.json, keeping any rules that were skipped
+/// on load. Refuses when the file couldn't be read, so a typo never gets
+/// replaced by defaults.
+pub fn save_config(config: &Config) -> Result<(), AppError> {
+    if let Some(error) = &config.load_error {
+        return Err(AppError::Conflict(format!(
+            "Settings weren't saved because {}. Fix the file and try again",
+            error
+        )));
+    }
+    let Some(dir) = ensure_dir() else {
+        return Ok(());
     };
-    serde_json::from_str(&data).unwrap_or_default()
+    let mut value = serde_json::to_value(config)
+        .map_err(|e| AppError::General(format!("Couldn't save config: {}", e)))?;
+    if let Some(rules) = value.get_mut("health_rules").and_then(|r| r.as_array_mut()) {
+        rules.extend(config.invalid_rules.iter().cloned());
+    }
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| AppError::General(format!("Couldn't save config: {}", e)))?;
+    fs::write(dir.join("config.json"), json)?;
+    Ok(())
+}
+
+/// Everything wrong with config.json, for showing to the user.
+pub fn config_problems() -> Vec<String> {
+    let config = load_config();
+    config.load_error.into_iter().chain(config.rule_errors).collect()
 }
 
-pub fn save_config(config: &Config) {
-    let Some(dir) = ensure_dir() else { return };
-    if let Ok(json) = serde_json::to_string_pretty(config) {
-        let _ = fs::write(dir.join("config.json"), json);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_rule_is_skipped_and_kept_for_saving() {
+        let config = parse_config(
+            r#"{ "watch": true, "health_rules": [
+                { "id": "ok", "severity": "warning", "reason": "r",
+                  "when": [{ "metric": "ahead", "op": ">", "value": 1 }] },
+                { "id": "typo", "severity": "warning", "reason": "r",
+                  "when": [{ "metric": "aheed", "op": ">", "value": 1 }] }
+            ] }"#,
+        )
+        .unwrap();
+        assert!(config.watch);
+        assert_eq!(config.health_rules.len(), 1);
+        assert_eq!(config.health_rules[0].id, "ok");
+        assert_eq!(config.rule_errors.len(), 1);
+        assert!(config.rule_errors[0].contains("'typo'"));
+        assert_eq!(config.invalid_rules.len(), 1);
+    }
+
+    #[test]
+    fn missing_rules_fall_back_to_defaults() {
+        let config = parse_config(r#"{ "clone_layout": "{root}/{repo}" }"#).unwrap();
+        assert_eq!(config.clone_layout, "{root}/{repo}");
+        assert_eq!(config.health_rules.len(), health::default_rules().len());
+        assert!(config.rule_errors.is_empty());
+    }
+
+    #[test]
+    fn malformed_file_is_an_error() {
+        assert!(parse_config("{ \"watch\": ").is_err());
+        assert!(parse_config(r#"{ "health_rules": {} }"#).is_err());
+    }
+
+    #[test]
+    fn failed_load_refuses_to_save() {
+        let config = failed_load("expected value".to_string());
+        assert!(config.load_error.is_some());
+        assert!(save_config(&config).is_err());
     }
 }
@@ -1,32 +1,39 @@
 use std::path::Path;
 
-use crate::db::models::{BranchInfo, CommitFileChange, CommitInfo, FileChange, GitProfile, RemoteInfo, StashEntry};
-use crate::error::AppError;
+use tauri::State;
+
+use crate::cache;
+use crate::db::models::{
+    BranchInfo, CommitFileChange, CommitInfo, FileChange, GitProfile, RemoteInfo, StashEntry, SubmoduleInfo,
+    WorktreeInfo,
+};
+use crate::error::{AppError, ResultExt};
 use crate::git;
+use crate::AppState;
 
 // ── Commit log ──────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_commit_log(path: String, count: Option<usize>) -> Result<Vec<CommitInfo>, AppError> {
     let limit = count.unwrap_or(100);
-    git::detail::get_commit_log(Path::new(&path), limit)
+    git::detail::get_commit_log(Path::new(&path), limit).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn get_commit_diff(path: String, oid: String) -> Result<String, AppError> {
-    git::detail::get_commit_diff(Path::new(&path), &oid)
+    git::detail::get_commit_diff(Path::new(&path), &oid).in_repo(&path)
 }
 
 // ── File changes & diff ─────────────────────────────────
 
 #[tauri::command]
 pub async fn get_file_changes(path: String) -> Result<Vec<FileChange>, AppError> {
-    git::detail::get_file_changes(Path::new(&path))
+    git::detail::get_file_changes(Path::new(&path)).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn get_file_diff(path: String, file_path: String, staged: bool) -> Result<String, AppError> {
-    git::detail::get_file_diff(Path::new(&path), &file_path, staged)
+    git::detail::get_file_diff(Path::new(&path), &file_path, staged).in_repo(&path)
 }
 
 // ── Staging ─────────────────────────────────────────────
@@ -34,94 +41,160 @@ pub async fn get_file_diff(path: String, file_path: String, staged: bool) -> Res
 #[tauri::command]
 pub async fn stage_files(path: String, files: Vec<String>) -> Result<Vec<FileChange>, AppError> {
     let p = Path::new(&path);
-    git::detail::stage_files(p, &files)?;
-    git::detail::get_file_changes(p)
+    git::detail::stage_files(p, &files).in_repo(&path)?;
+    git::detail::get_file_changes(p).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn unstage_files(path: String, files: Vec<String>) -> Result<Vec<FileChange>, AppError> {
     let p = Path::new(&path);
-    git::detail::unstage_files(p, &files)?;
-    git::detail::get_file_changes(p)
+    git::detail::unstage_files(p, &files).in_repo(&path)?;
+    git::detail::get_file_changes(p).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn stage_all_files(path: String) -> Result<Vec<FileChange>, AppError> {
     let p = Path::new(&path);
-    git::detail::stage_all(p)?;
-    git::detail::get_file_changes(p)
+    git::detail::stage_all(p).in_repo(&path)?;
+    git::detail::get_file_changes(p).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn unstage_all_files(path: String) -> Result<Vec<FileChange>, AppError> {
     let p = Path::new(&path);
-    git::detail::unstage_all(p)?;
-    git::detail::get_file_changes(p)
+    git::detail::unstage_all(p).in_repo(&path)?;
+    git::detail::get_file_changes(p).in_repo(&path)
 }
 
 // ── Commit ──────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn create_commit(path: String, message: String) -> Result<String, AppError> {
-    git::detail::create_commit(Path::new(&path), &message)
+    git::detail::create_commit(Path::new(&path), &message).in_repo(&path)
 }
 
 // ── Branches ────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_branches(path: String) -> Result<Vec<BranchInfo>, AppError> {
-    git::detail::get_branches(Path::new(&path))
+    git::detail::get_branches(Path::new(&path)).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn checkout_branch(path: String, branch_name: String) -> Result<(), AppError> {
-    git::detail::checkout_branch(Path::new(&path), &branch_name)
+    git::detail::checkout_branch(Path::new(&path), &branch_name).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn create_branch(path: String, branch_name: String) -> Result<(), AppError> {
-    git::detail::create_branch(Path::new(&path), &branch_name)
+    git::detail::create_branch(Path::new(&path), &branch_name).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn delete_branch(path: String, branch_name: String) -> Result<(), AppError> {
-    git::detail::delete_branch(Path::new(&path), &branch_name)
+    git::detail::delete_branch(Path::new(&path), &branch_name).in_repo(&path)
+}
+
+// ── Worktrees ───────────────────────────────────────────
+
+fn list_worktrees(path: &str) -> Result<Vec<WorktreeInfo>, AppError> {
+    let rules = cache::load_config().health_rules;
+    git::worktrees::list(Path::new(path), &rules).in_repo(path)
+}
+
+#[tauri::command]
+pub async fn get_worktrees(path: String) -> Result<Vec<WorktreeInfo>, AppError> {
+    list_worktrees(&path)
+}
+
+/// Check a branch out into a new worktree and add it to the repo list (and
+/// the watcher, if live updates are on).
+#[tauri::command]
+pub async fn add_worktree(
+    path: String,
+    worktree_path: String,
+    branch_name: String,
+    create_branch: bool,
+    state: State<'_, AppState>,
+) -> Result<Vec<WorktreeInfo>, AppError> {
+    let added = git::worktrees::add(Path::new(&path), Path::new(&worktree_path), &branch_name, create_branch)
+        .in_repo(&path)?;
+    let worktrees = list_worktrees(&path)?;
+    let added = added.to_string_lossy();
+    if let Some(status) = worktrees.iter().find(|w| w.path == added).and_then(|w| w.status.as_ref()) {
+        state.db.upsert_repo(status)?;
+        super::watch::watch_new_repo(&state, &status.path);
+    }
+    Ok(worktrees)
+}
+
+#[tauri::command]
+pub async fn lock_worktree(path: String, name: String, reason: Option<String>) -> Result<Vec<WorktreeInfo>, AppError> {
+    git::worktrees::lock(Path::new(&path), &name, reason.as_deref()).in_repo(&path)?;
+    list_worktrees(&path)
+}
+
+#[tauri::command]
+pub async fn unlock_worktree(path: String, name: String) -> Result<Vec<WorktreeInfo>, AppError> {
+    git::worktrees::unlock(Path::new(&path), &name).in_repo(&path)?;
+    list_worktrees(&path)
+}
+
+#[tauri::command]
+pub async fn prune_worktrees(path: String, state: State<'_, AppState>) -> Result<Vec<WorktreeInfo>, AppError> {
+    let pruned = git::worktrees::prune(Path::new(&path)).in_repo(&path)?;
+    let pruned: Vec<String> = pruned.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    state.db.remove_repos(&pruned)?;
+    list_worktrees(&path)
+}
+
+/// Delete a worktree and drop it from the repo list.
+#[tauri::command]
+pub async fn remove_worktree(
+    path: String,
+    name: String,
+    force: bool,
+    state: State<'_, AppState>,
+) -> Result<Vec<WorktreeInfo>, AppError> {
+    let removed = git::worktrees::remove(Path::new(&path), &name, force).in_repo(&path)?;
+    state.db.remove_repos(&[removed.to_string_lossy().to_string()])?;
+    list_worktrees(&path)
 }
 
 // ── Stashes ─────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_stashes(path: String) -> Result<Vec<StashEntry>, AppError> {
-    git::detail::get_stashes(Path::new(&path))
+    git::detail::get_stashes(Path::new(&path)).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn stash_save(path: String, message: String) -> Result<(), AppError> {
-    git::detail::stash_save(Path::new(&path), &message)
+    git::detail::stash_save(Path::new(&path), &message).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn stash_pop(path: String, index: usize) -> Result<(), AppError> {
-    git::detail::stash_pop(Path::new(&path), index)
+    git::detail::stash_pop(Path::new(&path), index).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn stash_drop(path: String, index: usize) -> Result<(), AppError> {
-    git::detail::stash_drop(Path::new(&path), index)
+    git::detail::stash_drop(Path::new(&path), index).in_repo(&path)
 }
 
 // ── Commit files ─────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_commit_files(path: String, oid: String) -> Result<Vec<CommitFileChange>, AppError> {
-    git::detail::get_commit_files(Path::new(&path), &oid)
+    git::detail::get_commit_files(Path::new(&path), &oid).in_repo(&path)
 }
 
 // ── Merge ─────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn merge_branch(path: String, branch_name: String) -> Result<String, AppError> {
-    git::detail::merge_branch(Path::new(&path), &branch_name)
+    git::detail::merge_branch(Path::new(&path), &branch_name).in_repo(&path)
 }
 
 // ── File history ─────────────────────────────────────────
@@ -129,60 +202,90 @@ pub async fn merge_branch(path: String, branch_name: String) -> Result<String, A
 #[tauri::command]
 pub async fn get_file_history(path: String, file_path: String, count: Option<usize>) -> Result<Vec<CommitInfo>, AppError> {
     let limit = count.unwrap_or(50);
-    git::detail::get_file_history(Path::new(&path), &file_path, limit)
+    git::detail::get_file_history(Path::new(&path), &file_path, limit).in_repo(&path)
 }
 
 // ── Remotes ─────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_remotes(path: String) -> Result<Vec<RemoteInfo>, AppError> {
-    git::detail::get_remotes(Path::new(&path))
+    git::detail::get_remotes(Path::new(&path)).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn add_remote(path: String, name: String, url: String) -> Result<(), AppError> {
-    git::detail::add_remote(Path::new(&path), &name, &url)
+    git::detail::add_remote(Path::new(&path), &name, &url).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn remove_remote(path: String, name: String) -> Result<(), AppError> {
-    git::detail::remove_remote(Path::new(&path), &name)
+    git::detail::remove_remote(Path::new(&path), &name).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn rename_remote(path: String, old_name: String, new_name: String) -> Result<(), AppError> {
-    git::detail::rename_remote(Path::new(&path), &old_name, &new_name)
+    git::detail::rename_remote(Path::new(&path), &old_name, &new_name).in_repo(&path)
+}
+
+// ── Submodules ──────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_submodules(path: String) -> Result<Vec<SubmoduleInfo>, AppError> {
+    git::submodules::list(Path::new(&path)).in_repo(&path)
+}
+
+/// An empty `names` initializes every submodule.
+#[tauri::command]
+pub async fn init_submodules(path: String, names: Vec<String>) -> Result<Vec<SubmoduleInfo>, AppError> {
+    let p = Path::new(&path);
+    git::submodules::init(p, &names).in_repo(&path)?;
+    git::submodules::list(p).in_repo(&path)
+}
+
+/// An empty `names` syncs every submodule.
+#[tauri::command]
+pub async fn sync_submodules(path: String, names: Vec<String>) -> Result<Vec<SubmoduleInfo>, AppError> {
+    let p = Path::new(&path);
+    git::submodules::sync(p, &names).in_repo(&path)?;
+    git::submodules::list(p).in_repo(&path)
+}
+
+#[tauri::command]
+pub async fn deinit_submodule(path: String, name: String, force: bool) -> Result<Vec<SubmoduleInfo>, AppError> {
+    let p = Path::new(&path);
+    git::submodules::deinit(p, &name, force).in_repo(&path)?;
+    git::submodules::list(p).in_repo(&path)
 }
 
 // ── Git profile ─────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_git_profile(path: String) -> Result<GitProfile, AppError> {
-    git::detail::get_git_profile(Path::new(&path))
+    git::detail::get_git_profile(Path::new(&path)).in_repo(&path)
 }
 
 #[tauri::command]
 pub async fn set_git_profile(path: String, name: String, email: String) -> Result<(), AppError> {
-    git::detail::set_git_profile(Path::new(&path), &name, &email)
+    git::detail::set_git_profile(Path::new(&path), &name, &email).in_repo(&path)
 }
 
 // ── Squash ──────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn squash_commits(path: String, count: usize, message: String) -> Result<String, AppError> {
-    git::detail::squash_commits(Path::new(&path), count, &message)
+    git::detail::squash_commits(Path::new(&path), count, &message).in_repo(&path)
 }
 
 // ── PR URL ──────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_pr_url(path: String) -> Result<String, AppError> {
-    git::detail::get_pr_url(Path::new(&path))
+    git::detail::get_pr_url(Path::new(&path)).in_repo(&path)
 }
 
 // ── README ─────────────────────────────────────────────
 
 #[tauri::command]
 pub async fn get_readme(path: String) -> Result<Option<String>, AppError> {
-    git::detail::get_readme(Path::new(&path))
+    git::detail::get_readme(Path::new(&path)).in_repo(&path)
 }
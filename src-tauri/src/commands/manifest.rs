@@ -0,0 +1,177 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+
+use crate::cache;
+use crate::db::models::{ManifestEntryResult, ManifestOutcome, ManifestSyncReport, RepoKind};
+use crate::error::AppError;
+use crate::git;
+use crate::jobs;
+use crate::manifest::{self, ManifestRepo};
+use crate::AppState;
+
+use super::operations::{op_context, target_root};
+use super::watch::watch_new_repo;
+
+/// Write every known repo to `file` as a native manifest, with paths
+/// relative to their scan root. Returns the number of repos written.
+#[tauri::command]
+pub async fn export_manifest(file: String, state: State<'_, AppState>) -> Result<usize, AppError> {
+    let repos = state.db.get_all_repos()?;
+    let tags = state.db.get_repo_tags()?;
+    let roots = super::scan::roots_or_default(cache::load_config().scan_roots);
+
+    // Remotes are read from each repo's config rather than the database
+    let manifest = tokio::task::spawn_blocking(move || manifest::export(&repos, &tags, &roots))
+        .await
+        .map_err(|e| AppError::General(format!("Export failed: {}", e)))?;
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| AppError::General(format!("Export failed: {}", e)))?;
+    fs::write(&file, json)?;
+    Ok(manifest.repos.len())
+}
+
+/// Compare a manifest (native, Google `repo` XML or a URL list) with what is
+/// under `root`, or the first enabled scan root. Repos already there are
+/// checked for mismatched remotes and default branch and added to the repo
+/// list; missing ones are cloned when `clone_missing` is set, sharing the
+/// bulk concurrency limits and `operation_id` for cancellation. Known repos
+/// under the root that the manifest doesn't list are reported as extra.
+#[tauri::command]
+pub async fn sync_manifest(
+    file: String,
+    root: Option<String>,
+    clone_missing: bool,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ManifestSyncReport, AppError> {
+    let manifest_url = manifest::manifest_url(Path::new(&file));
+    let (format, entries) = manifest::parse(&fs::read_to_string(&file)?, manifest_url.as_deref())?;
+    let config = cache::load_config();
+    let root = target_root(&config, root.as_deref())?;
+
+    let survey = {
+        let root = root.clone();
+        let layout = config.clone_layout.clone();
+        let rules = config.health_rules.clone();
+        tokio::task::spawn_blocking(move || manifest::survey(entries, &root, &layout, &rules))
+            .await
+            .map_err(|e| AppError::General(format!("Manifest sync failed: {}", e)))?
+    };
+
+    let mut results = survey.results;
+    for (info, tags) in &survey.found {
+        state.db.upsert_repo(info)?;
+        if !tags.is_empty() {
+            state.db.set_repo_tags(&info.path, tags)?;
+        }
+        watch_new_repo(&state, &info.path);
+    }
+
+    if clone_missing && !survey.missing.is_empty() {
+        results.extend(clone_all(&app, &state, &config, survey.missing, operation_id).await?);
+    } else {
+        results.extend(survey.missing.into_iter().map(|(dest, entry)| ManifestEntryResult {
+            path: dest.to_string_lossy().to_string(),
+            url: entry.clone_url().map(String::from),
+            outcome: ManifestOutcome::Missing,
+        }));
+    }
+    results.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let listed: HashSet<&str> = results.iter().map(|r| r.path.as_str()).collect();
+    let extra = state
+        .db
+        .get_all_repos()?
+        .into_iter()
+        .filter(|repo| {
+            !matches!(repo.kind, RepoKind::Worktree | RepoKind::Submodule)
+                && Path::new(&repo.path).starts_with(&root)
+                && !listed.contains(repo.path.as_str())
+        })
+        .map(|repo| repo.path)
+        .collect();
+
+    Ok(ManifestSyncReport {
+        format,
+        root: root.to_string_lossy().to_string(),
+        entries: results,
+        extra,
+    })
+}
+
+/// Clone the missing entries, adding each to the repo list as it lands.
+async fn clone_all(
+    app: &AppHandle,
+    state: &AppState,
+    config: &cache::Config,
+    missing: Vec<(PathBuf, ManifestRepo)>,
+    operation_id: Option<String>,
+) -> Result<Vec<ManifestEntryResult>, AppError> {
+    let limits = jobs::Limits {
+        total: config.bulk_concurrency,
+        per_host: config.per_host_concurrency,
+    };
+    let jobs = missing
+        .iter()
+        .map(|(dest, entry)| jobs::Job {
+            path: dest.clone(),
+            remote_url: entry.clone_url().map(String::from),
+        })
+        .collect();
+    let entries: Arc<HashMap<PathBuf, ManifestRepo>> = Arc::new(missing.into_iter().collect());
+
+    let rules = config.health_rules.clone();
+    let ctx = op_context(app, state, operation_id.as_deref());
+    let pending = entries.clone();
+    let failed = move |dest: &Path, message: String| {
+        (dest.to_path_buf(), pending[dest].clone(), Err(AppError::General(message)))
+    };
+    let work = move |dest: &Path| {
+        let entry = entries[dest].clone();
+        let result = manifest::clone_entry(&entry, dest, &ctx)
+            .map(|()| git::status::get_repo_info(dest, &rules));
+        (dest.to_path_buf(), entry, result)
+    };
+
+    let mut db_error = None;
+    let cloned = jobs::run(jobs, limits, work, failed, |(_, entry, result)| {
+        if let Ok(info) = result {
+            let stored = state
+                .db
+                .upsert_repo(info)
+                .and_then(|()| state.db.set_repo_tags(&info.path, &entry.tags));
+            if let Err(e) = stored {
+                db_error.get_or_insert(e);
+            }
+            watch_new_repo(state, &info.path);
+        }
+    })
+    .await;
+
+    if let Some(id) = &operation_id {
+        state.operations.finish(id);
+    }
+    if let Some(e) = db_error {
+        return Err(e);
+    }
+
+    Ok(cloned
+        .into_iter()
+        .map(|(dest, entry, result)| {
+            let url = entry.clone_url().map(String::from);
+            match result {
+                Ok(info) => ManifestEntryResult {
+                    path: info.path,
+                    url,
+                    outcome: ManifestOutcome::Cloned,
+                },
+                Err(e) => manifest::failed(dest.to_string_lossy().to_string(), url, e),
+            }
+        })
+        .collect())
+}
@@ -1,4 +1,6 @@
 pub mod detail;
+pub mod manifest;
 pub mod operations;
 pub mod scan;
 pub mod status;
+pub mod watch;
@@ -1,64 +1,387 @@
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 
-use tauri::State;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
 
 use crate::cache;
-use crate::db::models::RepoInfo;
-use crate::error::AppError;
+use crate::db::models::{
+    BulkReport, BulkSummary, OperationOutcome, RepoInfo, RepoOperationResult,
+};
+use crate::error::{AppError, ErrorCategory, ResultExt};
 use crate::git;
+use crate::git::progress::{NetworkProgress, OpContext};
+use crate::jobs;
 use crate::AppState;
 
+/// Emitted once per repo as a bulk fetch/pull finishes with it.
+const BULK_RESULT_EVENT: &str = "bulk-repo-result";
+
+/// Emitted with object/byte counts while a fetch or push transfers data.
+const PROGRESS_EVENT: &str = "git-progress";
+
+#[derive(Debug, Clone, Serialize)]
+struct BulkRepoEvent {
+    operation: &'static str,
+    result: RepoOperationResult,
+    completed: usize,
+    total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ProgressEvent {
+    operation_id: Option<String>,
+    path: String,
+    #[serde(flatten)]
+    progress: NetworkProgress,
+}
+
+/// Build the context for a network operation: progress is emitted as Tauri
+/// events, and if the frontend supplied an id the operation is registered so
+/// `cancel_operation` can stop it.
+pub(super) fn op_context(app: &AppHandle, state: &AppState, operation_id: Option<&str>) -> OpContext {
+    let cancel = operation_id
+        .map(|id| state.operations.register(id))
+        .unwrap_or_default();
+    let app = app.clone();
+    let operation_id = operation_id.map(String::from);
+    OpContext {
+        progress: Some(Arc::new(move |path: &Path, progress| {
+            let _ = app.emit(
+                PROGRESS_EVENT,
+                ProgressEvent {
+                    operation_id: operation_id.clone(),
+                    path: path.to_string_lossy().to_string(),
+                    progress,
+                },
+            );
+        })),
+        cancel,
+    }
+}
+
+#[tauri::command]
+pub async fn fetch_all(
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BulkReport, AppError> {
+    run_bulk(&app, &state, "fetch", operation_id, git::operations::fetch_repo).await
+}
+
 #[tauri::command]
-pub async fn fetch_all(state: State<'_, AppState>) -> Result<Vec<RepoInfo>, AppError> {
-    let repos = state.db.get_all_repos()?;
-    let mut results = Vec::new();
-
-    for repo in &repos {
-        let path = Path::new(&repo.path);
-        let _ = git::operations::fetch_repo(path);
-        let updated = git::status::get_repo_info(path);
-        state.db.upsert_repo(&updated)?;
-        results.push(updated);
+pub async fn pull_all(
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BulkReport, AppError> {
+    run_bulk(&app, &state, "pull", operation_id, git::operations::pull_rebase_repo).await
+}
+
+/// Run `op` over every repo in the database on the job runner, persisting
+/// and emitting each repo's outcome and refreshed status as soon as it is ready.
+/// Cancelling `operation_id` stops in-flight transfers and skips repos not yet started.
+async fn run_bulk<W>(
+    app: &AppHandle,
+    state: &AppState,
+    operation: &'static str,
+    operation_id: Option<String>,
+    op: W,
+) -> Result<BulkReport, AppError>
+where
+    W: Fn(&Path, &OpContext) -> Result<(), AppError> + Send + Sync + 'static,
+{
+    let config = cache::load_config();
+    let limits = jobs::Limits {
+        total: config.bulk_concurrency,
+        per_host: config.per_host_concurrency,
+    };
+
+    let known: HashMap<PathBuf, RepoInfo> = state
+        .db
+        .get_all_repos()?
+        .into_iter()
+        .map(|repo| (PathBuf::from(&repo.path), repo))
+        .collect();
+    let jobs: Vec<jobs::Job> = known
+        .iter()
+        .map(|(path, repo)| jobs::Job {
+            path: path.clone(),
+            remote_url: repo.remote_url.clone(),
+        })
+        .collect();
+    let total = jobs.len();
+
+    let measured: HashMap<PathBuf, u32> = known
+        .iter()
+        .map(|(path, repo)| (path.clone(), repo.ignored_large_files))
+        .collect();
+    let rules = config.health_rules;
+    let ctx = op_context(app, state, operation_id.as_deref());
+    let work = move |path: &Path| {
+        let started = Instant::now();
+        let outcome = outcome_of(op(path, &ctx));
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let mut repo = git::status::get_repo_info(path, &rules);
+        git::status::keep_ignored_large(&mut repo, measured[path], &rules);
+        RepoOperationResult {
+            path: repo.path.clone(),
+            name: repo.name.clone(),
+            outcome,
+            duration_ms,
+            repo,
+        }
+    };
+
+    // A job that panicked keeps its last stored status
+    let failed = |path: &Path, message: String| {
+        let repo = known[path].clone();
+        RepoOperationResult {
+            path: repo.path.clone(),
+            name: repo.name.clone(),
+            outcome: OperationOutcome::Failed {
+                category: ErrorCategory::Other,
+                message,
+            },
+            duration_ms: 0,
+            repo,
+        }
+    };
+
+    let mut completed = 0;
+    let mut db_error = None;
+    let mut results = jobs::run(jobs, limits, work, failed, |result: &RepoOperationResult| {
+        completed += 1;
+        if let Err(e) = state.db.upsert_repo(&result.repo) {
+            db_error.get_or_insert(e);
+        }
+        let _ = app.emit(
+            BULK_RESULT_EVENT,
+            BulkRepoEvent {
+                operation,
+                result: result.clone(),
+                completed,
+                total,
+            },
+        );
+    })
+    .await;
+
+    if let Some(id) = &operation_id {
+        state.operations.finish(id);
+    }
+    if let Some(e) = db_error {
+        return Err(e);
+    }
+
+    results.sort_by_key(|r| r.name.to_lowercase());
+    let repos: Vec<RepoInfo> = results.iter().map(|r| r.repo.clone()).collect();
+    state.db.record_snapshots(&repos)?;
+
+    Ok(BulkReport {
+        operation: operation.to_string(),
+        summary: BulkSummary::from_results(&results),
+        results,
+    })
+}
+
+/// Missing preconditions (nothing to fetch from, nothing to rebase onto) and
+/// cancellation are reported as skips; everything else is a categorized failure.
+fn outcome_of(result: Result<(), AppError>) -> OperationOutcome {
+    let Err(e) = result else {
+        return OperationOutcome::Success;
+    };
+    match e.category() {
+        ErrorCategory::NoRemote
+        | ErrorCategory::DetachedHead
+        | ErrorCategory::NoUpstream
+        | ErrorCategory::Cancelled => OperationOutcome::Skipped {
+            reason: e.to_string(),
+        },
+        category => OperationOutcome::Failed {
+            category,
+            message: e.to_string(),
+        },
     }
+}
+
+/// Run a single-repo network operation on a blocking worker thread and
+/// return the repo's refreshed status.
+async fn run_single<W>(
+    app: &AppHandle,
+    state: &AppState,
+    path: String,
+    operation_id: Option<String>,
+    op: W,
+) -> Result<RepoInfo, AppError>
+where
+    W: FnOnce(&Path, &OpContext) -> Result<(), AppError> + Send + 'static,
+{
+    let rules = cache::load_config().health_rules;
+    let measured = state.db.get_ignored_large_files(&path)?;
+    let ctx = op_context(app, state, operation_id.as_deref());
+    let result = tokio::task::spawn_blocking(move || {
+        let repo_path = Path::new(&path);
+        op(repo_path, &ctx).in_repo(&path)?;
+        let mut info = git::status::get_repo_info(repo_path, &rules);
+        git::status::keep_ignored_large(&mut info, measured, &rules);
+        Ok(info)
+    })
+    .await
+    .unwrap_or_else(|e| Err(AppError::General(format!("Operation failed: {}", e))));
 
-    cache::save(&results);
-    Ok(results)
+    if let Some(id) = &operation_id {
+        state.operations.finish(id);
+    }
+    result
 }
 
 #[tauri::command]
-pub async fn pull_all(state: State<'_, AppState>) -> Result<Vec<RepoInfo>, AppError> {
-    let repos = state.db.get_all_repos()?;
-    let mut results = Vec::new();
-
-    for repo in &repos {
-        let path = Path::new(&repo.path);
-        let _ = git::operations::pull_rebase_repo(path);
-        let updated = git::status::get_repo_info(path);
-        state.db.upsert_repo(&updated)?;
-        results.push(updated);
+pub async fn fetch_repo(
+    path: String,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    run_single(&app, &state, path, operation_id, git::operations::fetch_repo).await
+}
+
+#[tauri::command]
+pub async fn pull_rebase_repo(
+    path: String,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    run_single(&app, &state, path, operation_id, git::operations::pull_rebase_repo).await
+}
+
+#[tauri::command]
+pub async fn push_repo(
+    path: String,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    run_single(&app, &state, path, operation_id, git::operations::push_repo).await
+}
+
+/// Clone and check out submodules at their recorded commits; an empty
+/// `names` updates all of them. Reports transfer progress like a fetch.
+#[tauri::command]
+pub async fn update_submodules(
+    path: String,
+    names: Vec<String>,
+    init: bool,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    run_single(&app, &state, path, operation_id, move |path, ctx| {
+        git::submodules::update(path, &names, init, ctx)
+    })
+    .await
+}
+
+/// Where cloning `url` into `root` would put it, following the configured
+/// layout. `root` must be one of the scan roots; by default the first enabled
+/// one is used.
+fn clone_destination(config: &cache::Config, url: &str, root: Option<&str>) -> Result<PathBuf, AppError> {
+    let root = target_root(config, root)?;
+    git::clone::destination(&config.clone_layout, &root, url)
+}
+
+/// The scan root named `root`, or the first enabled one, to clone into.
+pub(super) fn target_root(config: &cache::Config, root: Option<&str>) -> Result<PathBuf, AppError> {
+    let roots = super::scan::roots_or_default(config.scan_roots.clone());
+    let root = match root {
+        Some(path) => roots.iter().find(|r| r.path == path).ok_or_else(|| {
+            AppError::General(format!("{} is not a scan root", path))
+        })?,
+        None => roots
+            .iter()
+            .find(|r| r.enabled)
+            .ok_or_else(|| AppError::General("No enabled scan root to clone into".to_string()))?,
+    };
+    Ok(PathBuf::from(&root.path))
+}
+
+#[tauri::command]
+pub async fn get_clone_destination(url: String, root: Option<String>) -> Result<String, AppError> {
+    let dest = clone_destination(&cache::load_config(), &url, root.as_deref())?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Clone `url` under a scan root according to the clone layout and add it to
+/// the repo list (and the watcher, if live updates are on) without a rescan.
+/// Progress is reported under the destination path.
+#[tauri::command]
+pub async fn clone_repo(
+    url: String,
+    root: Option<String>,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    let config = cache::load_config();
+    let dest = clone_destination(&config, &url, root.as_deref())?;
+    let rules = config.health_rules;
+    let ctx = op_context(&app, &state, operation_id.as_deref());
+    let result = tokio::task::spawn_blocking(move || {
+        git::clone::clone_repo(&url, &dest, &ctx)?;
+        Ok(git::status::get_repo_info(&dest, &rules))
+    })
+    .await
+    .unwrap_or_else(|e| Err(AppError::General(format!("Clone failed: {}", e))));
+
+    if let Some(id) = &operation_id {
+        state.operations.finish(id);
     }
+    let info = result?;
+    state.db.upsert_repo(&info)?;
+    super::watch::watch_new_repo(&state, &info.path);
+    Ok(info)
+}
 
-    cache::save(&results);
-    Ok(results)
+/// Continue the merge, rebase, cherry-pick or revert the repo was left in.
+#[tauri::command]
+pub async fn continue_in_progress(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    run_single(&app, &state, path, None, |path, _| {
+        git::state::continue_operation(path)
+    })
+    .await
 }
 
 #[tauri::command]
-pub async fn fetch_repo(path: String) -> Result<RepoInfo, AppError> {
-    let repo_path = Path::new(&path);
-    git::operations::fetch_repo(repo_path)?;
-    Ok(git::status::get_repo_info(repo_path))
+pub async fn skip_in_progress(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    run_single(&app, &state, path, None, |path, _| git::state::skip_operation(path)).await
 }
 
 #[tauri::command]
-pub async fn pull_rebase_repo(path: String) -> Result<RepoInfo, AppError> {
-    let repo_path = Path::new(&path);
-    git::operations::pull_rebase_repo(repo_path)?;
-    Ok(git::status::get_repo_info(repo_path))
+pub async fn abort_in_progress(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    run_single(&app, &state, path, None, |path, _| git::state::abort_operation(path)).await
 }
 
+/// Cancel a running fetch, pull, push, scan or bulk run. Returns false if no
+/// operation with that id is in flight.
 #[tauri::command]
-pub async fn push_repo(path: String) -> Result<RepoInfo, AppError> {
-    let repo_path = Path::new(&path);
-    git::operations::push_repo(repo_path)?;
-    Ok(git::status::get_repo_info(repo_path))
+pub async fn cancel_operation(
+    operation_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    Ok(state.operations.cancel(&operation_id))
 }
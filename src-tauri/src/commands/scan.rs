@@ -1,66 +1,122 @@
 use std::path::PathBuf;
 
-use tauri::State;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
 
 use crate::cache;
 use crate::db::models::RepoInfo;
 use crate::error::AppError;
+use crate::git::discovery::ScanRoot;
+use crate::git::progress::CancelToken;
+use crate::scanner::{self, ScanEvent};
 use crate::AppState;
-use crate::scanner;
 
+/// Emitted as a scan discovers repos, finishes reading their status, and
+/// walks directories.
+const SCAN_EVENT: &str = "scan-event";
+
+#[derive(Debug, Clone, Serialize)]
+struct ScanEventPayload {
+    operation_id: Option<String>,
+    #[serde(flatten)]
+    event: ScanEvent,
+}
+
+/// Scan the configured roots for repos, streaming each repo as it is discovered and as
+/// its status completes. Repos whose git metadata hasn't changed since the
+/// last scan keep their stored status unless `full` is set; repos that have
+/// disappeared are removed. Cancelling `operation_id` stops the scan, keeping
+/// the repos finished so far.
 #[tauri::command]
 pub async fn scan_directories(
-    roots: Vec<String>,
+    full: Option<bool>,
+    operation_id: Option<String>,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<Vec<RepoInfo>, AppError> {
-    let root_paths: Vec<PathBuf> = roots.into_iter().map(PathBuf::from).collect();
-    let repos = scanner::scan_roots(&root_paths);
+    let config = cache::load_config();
+    let roots = roots_or_default(config.scan_roots);
+    let rules = config.health_rules;
+    let known = state.db.get_all_repos()?;
+    let cancel = match &operation_id {
+        Some(id) => state.operations.register(id),
+        None => CancelToken::default(),
+    };
 
-    // Persist to database
-    let db = &state.db;
-    db.clear_repos()?;
-    for repo in &repos {
-        db.upsert_repo(repo)?;
+    let event_id = operation_id.clone();
+    let outcome = tokio::task::spawn_blocking(move || {
+        let on_event = |event: ScanEvent| {
+            let _ = app.emit(
+                SCAN_EVENT,
+                ScanEventPayload {
+                    operation_id: event_id.clone(),
+                    event,
+                },
+            );
+        };
+        scanner::scan_roots(&roots, &rules, known, full.unwrap_or(false), &cancel, &on_event)
+    })
+    .await;
+    if let Some(id) = &operation_id {
+        state.operations.finish(id);
     }
+    let outcome = outcome.map_err(|e| AppError::General(format!("Scan failed: {}", e)))?;
 
-    // Write to disk cache for fast startup next time
-    cache::save(&repos);
+    // Persist to database (also serves as the cache for fast startup next time)
+    state.db.upsert_repos(&outcome.repos)?;
+    if !outcome.complete {
+        return Err(AppError::Cancelled);
+    }
+    state.db.remove_repos(&outcome.removed)?;
+    state.db.record_snapshots(&outcome.repos)?;
+
+    // Keep live updates in step with the repos just found
+    if let Some(watcher) = state.watcher.lock().unwrap().as_mut() {
+        let paths: Vec<PathBuf> = outcome.repos.iter().map(|r| PathBuf::from(&r.path)).collect();
+        watcher.sync(&paths);
+    }
 
-    Ok(repos)
+    Ok(outcome.repos)
 }
 
 #[tauri::command]
 pub async fn load_cached_repos(
     state: State<'_, AppState>,
 ) -> Result<Vec<RepoInfo>, AppError> {
-    let repos = cache::load();
-    if !repos.is_empty() {
-        let db = &state.db;
-        db.clear_repos()?;
-        for repo in &repos {
-            db.upsert_repo(repo)?;
-        }
-    }
-    Ok(repos)
+    state.db.get_all_repos()
 }
 
 #[tauri::command]
-pub async fn get_scan_roots() -> Result<Vec<String>, AppError> {
-    let config = cache::load_config();
-    if !config.scan_roots.is_empty() {
-        return Ok(config.scan_roots);
+pub async fn get_scan_roots() -> Result<Vec<ScanRoot>, AppError> {
+    Ok(roots_or_default(cache::load_config().scan_roots))
+}
+
+/// The configured roots, or `~/dev` when none are set.
+pub(crate) fn roots_or_default(roots: Vec<ScanRoot>) -> Vec<ScanRoot> {
+    if !roots.is_empty() {
+        return roots;
     }
     // Default fallback
     let home = dirs_next::home_dir()
         .map(|h| h.to_string_lossy().to_string())
         .unwrap_or_else(|| "/Users".to_string());
-    Ok(vec![format!("{}/dev", home)])
+    vec![ScanRoot::new(format!("{}/dev", home))]
 }
 
+/// Problems found reading config.json: a file that couldn't be parsed at
+/// all, or health rules that were skipped.
 #[tauri::command]
-pub async fn set_scan_roots(roots: Vec<String>) -> Result<(), AppError> {
+pub async fn get_config_problems() -> Result<Vec<String>, AppError> {
+    Ok(cache::config_problems())
+}
+
+#[tauri::command]
+pub async fn set_scan_roots(roots: Vec<ScanRoot>) -> Result<(), AppError> {
+    for root in &roots {
+        root.validate()?;
+    }
     let mut config = cache::load_config();
     config.scan_roots = roots;
-    cache::save_config(&config);
+    cache::save_config(&config)?;
     Ok(())
 }
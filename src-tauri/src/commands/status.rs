@@ -1,10 +1,16 @@
+use std::fs;
 use std::path::Path;
 
 use tauri::State;
 
-use crate::db::models::RepoInfo;
-use crate::error::AppError;
+use crate::cache;
+use crate::db::models::{
+    ReportFormat, RepoDiagnostics, RepoInfo, RepoQuery, RepoTrend, StatusSnapshot, UnpushedReport,
+    UnpushedWork,
+};
+use crate::error::{AppError, ResultExt};
 use crate::git;
+use crate::jobs;
 use crate::AppState;
 
 #[tauri::command]
@@ -12,11 +18,133 @@ pub async fn get_all_repos(state: State<'_, AppState>) -> Result<Vec<RepoInfo>,
     state.db.get_all_repos()
 }
 
+/// Stored repos sorted and filtered by recency (last commit, fetch, activity).
 #[tauri::command]
-pub async fn get_repo_status(path: String) -> Result<RepoInfo, AppError> {
+pub async fn query_repos(
+    query: RepoQuery,
+    state: State<'_, AppState>,
+) -> Result<Vec<RepoInfo>, AppError> {
+    state.db.query_repos(&query)
+}
+
+#[tauri::command]
+pub async fn get_repo_status(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
     let repo_path = Path::new(&path);
     if !repo_path.exists() {
-        return Err(AppError::General(format!("Path does not exist: {}", path)));
+        return Err(AppError::General(format!("Path does not exist: {}", path)).in_repo(&path));
+    }
+    let rules = cache::load_config().health_rules;
+    let mut info = git::status::get_repo_info(repo_path, &rules);
+    let measured = state.db.get_ignored_large_files(&info.path)?;
+    git::status::keep_ignored_large(&mut info, measured, &rules);
+    state.db.upsert_repo(&info)?;
+    state.db.record_snapshots(std::slice::from_ref(&info))?;
+    Ok(info)
+}
+
+/// Measure a repo's large ignored files, which scans leave out because the
+/// walk is too slow to repeat for every repo. The count is stored and kept
+/// by later scans until it's measured again.
+#[tauri::command]
+pub async fn measure_ignored_large_files(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<RepoInfo, AppError> {
+    if !Path::new(&path).exists() {
+        return Err(AppError::General(format!("Path does not exist: {}", path)).in_repo(&path));
     }
-    Ok(git::status::get_repo_info(repo_path))
+    let rules = cache::load_config().health_rules;
+    let info = tokio::task::spawn_blocking(move || {
+        let repo_path = Path::new(&path);
+        let measured = git::status::count_ignored_large(repo_path).in_repo(&path)?;
+        let mut info = git::status::get_repo_info(repo_path, &rules);
+        git::status::keep_ignored_large(&mut info, measured, &rules);
+        Ok::<_, AppError>(info)
+    })
+    .await
+    .map_err(|e| AppError::General(format!("Measuring ignored files failed: {}", e)))??;
+    state.db.upsert_repo(&info)?;
+    Ok(info)
+}
+
+/// Status snapshots for one repo over the last `days` days (default 30).
+#[tauri::command]
+pub async fn get_status_history(
+    path: String,
+    days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<StatusSnapshot>, AppError> {
+    let since = chrono::Utc::now() - chrono::Duration::days(days.unwrap_or(30) as i64);
+    state.db.get_status_history(&path, &since.to_rfc3339())
+}
+
+#[tauri::command]
+pub async fn get_repo_trends(state: State<'_, AppState>) -> Result<Vec<RepoTrend>, AppError> {
+    state.db.get_repo_trends()
+}
+
+/// Run deeper integrity checks on a repo. Walks the whole object database,
+/// so it runs on a blocking worker.
+#[tauri::command]
+pub async fn diagnose_repo(path: String) -> Result<RepoDiagnostics, AppError> {
+    if !Path::new(&path).exists() {
+        return Err(AppError::General(format!("Path does not exist: {}", path)).in_repo(&path));
+    }
+    tokio::task::spawn_blocking(move || git::diagnostics::diagnose(Path::new(&path)))
+        .await
+        .map_err(|e| AppError::General(format!("Diagnostics failed: {}", e)))
+}
+
+/// Check every known repo for work that exists only on this machine:
+/// unpushed commits, branches without an upstream, stashes, and uncommitted
+/// or untracked files.
+#[tauri::command]
+pub async fn get_unpushed_report(state: State<'_, AppState>) -> Result<UnpushedReport, AppError> {
+    unpushed_report(&state).await
+}
+
+/// Build the unpushed-work report and write it to `file` as Markdown or JSON.
+#[tauri::command]
+pub async fn export_unpushed_report(
+    file: String,
+    format: ReportFormat,
+    state: State<'_, AppState>,
+) -> Result<UnpushedReport, AppError> {
+    let report = unpushed_report(&state).await?;
+    let text = match format {
+        ReportFormat::Markdown => git::unpushed::to_markdown(&report),
+        ReportFormat::Json => serde_json::to_string_pretty(&report)
+            .map_err(|e| AppError::General(format!("Export failed: {}", e)))?,
+    };
+    fs::write(&file, text)?;
+    Ok(report)
+}
+
+/// Read the repos in parallel, bounded like a bulk operation. Nothing goes
+/// over the network, so the per-host limit never applies.
+async fn unpushed_report(state: &AppState) -> Result<UnpushedReport, AppError> {
+    let config = cache::load_config();
+    let limits = jobs::Limits {
+        total: config.bulk_concurrency,
+        per_host: config.per_host_concurrency,
+    };
+    let jobs = state
+        .db
+        .get_all_repos()?
+        .into_iter()
+        .map(|repo| jobs::Job {
+            path: repo.path.into(),
+            remote_url: None,
+        })
+        .collect();
+    // A repo whose read panicked is reported as unreadable rather than dropped
+    let failed = |path: &Path, message: String| UnpushedWork {
+        error: Some(message),
+        ..git::unpushed::read_nothing(path)
+    };
+    let works = jobs::run(jobs, limits, git::unpushed::read, failed, |_| {}).await;
+    Ok(git::unpushed::report(works))
 }
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, State};
+
+use crate::cache;
+use crate::error::AppError;
+use crate::watcher::RepoWatcher;
+use crate::AppState;
+
+/// Start live updates: watch every known repo and emit `repo-updated` as
+/// each one changes. Returns how many repos are being watched. The setting
+/// is remembered across launches. Watches are set up on a blocking thread,
+/// and the finished watcher replaces any running one.
+#[tauri::command]
+pub async fn start_watching(app: AppHandle, state: State<'_, AppState>) -> Result<usize, AppError> {
+    let paths: Vec<PathBuf> = state
+        .db
+        .get_all_repos()?
+        .into_iter()
+        .map(|repo| PathBuf::from(repo.path))
+        .collect();
+
+    // Save first, so a config that can't be written doesn't leave a watcher running
+    let mut config = cache::load_config();
+    config.watch = true;
+    cache::save_config(&config)?;
+
+    let (watcher, watching) = tokio::task::spawn_blocking(move || {
+        let mut watcher = RepoWatcher::start(app)?;
+        let watching = watcher.sync(&paths);
+        Ok::<_, AppError>((watcher, watching))
+    })
+    .await
+    .map_err(|e| AppError::General(format!("Cannot start file watcher: {}", e)))??;
+
+    let mut slot = state.watcher.lock().unwrap();
+    // Live updates may have been switched off again meanwhile
+    if cache::load_config().watch {
+        *slot = Some(watcher);
+    }
+    Ok(watching)
+}
+
+#[tauri::command]
+pub async fn stop_watching(state: State<'_, AppState>) -> Result<(), AppError> {
+    // Save first, so a watcher still being built sees the setting and
+    // isn't put in place after this one is dropped
+    let mut config = cache::load_config();
+    config.watch = false;
+    cache::save_config(&config)?;
+
+    state.watcher.lock().unwrap().take();
+    Ok(())
+}
+
+/// Start watching a repo that just joined the repo list (a clone, a new
+/// worktree, a repo found by a manifest sync), if live updates are on.
+pub(crate) fn watch_new_repo(state: &AppState, path: &str) {
+    if let Some(watcher) = state.watcher.lock().unwrap().as_mut() {
+        watcher.add(PathBuf::from(path));
+    }
+}
+
+#[tauri::command]
+pub async fn is_watching(state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.watcher.lock().unwrap().is_some())
+}
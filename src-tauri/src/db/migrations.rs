@@ -0,0 +1,174 @@
+use rusqlite::Connection;
+
+use crate::error::AppError;
+
+/// Ordered schema migrations. Each entry moves the database from version
+/// `index` to `index + 1`; the current version is kept in `PRAGMA user_version`.
+/// Never edit an entry once it has shipped — append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "CREATE TABLE IF NOT EXISTS repos (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        path TEXT NOT NULL UNIQUE,
+        name TEXT NOT NULL,
+        branch TEXT NOT NULL DEFAULT 'unknown',
+        ahead INTEGER NOT NULL DEFAULT 0,
+        behind INTEGER NOT NULL DEFAULT 0,
+        dirty_files INTEGER NOT NULL DEFAULT 0,
+        stash_count INTEGER NOT NULL DEFAULT 0,
+        health TEXT NOT NULL DEFAULT 'clean',
+        last_checked TEXT NOT NULL DEFAULT '',
+        remote_url TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS scan_roots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        path TEXT NOT NULL UNIQUE
+    );",
+    // 2: status history
+    "CREATE TABLE repo_snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        repo_path TEXT NOT NULL,
+        recorded_at TEXT NOT NULL,
+        branch TEXT NOT NULL,
+        ahead INTEGER NOT NULL,
+        behind INTEGER NOT NULL,
+        dirty_files INTEGER NOT NULL,
+        stash_count INTEGER NOT NULL,
+        health TEXT NOT NULL
+    );
+
+    CREATE INDEX idx_repo_snapshots_path_time ON repo_snapshots (repo_path, recorded_at);",
+    // 3: why a repo is in the error state
+    "ALTER TABLE repos ADD COLUMN error_detail TEXT;",
+    // 4: merge/rebase/etc. left in progress
+    "ALTER TABLE repos ADD COLUMN in_progress TEXT;",
+    // 5: configured upstream of the current branch
+    "ALTER TABLE repos ADD COLUMN upstream TEXT;",
+    // 6: drift from the remote default branch
+    "ALTER TABLE repos ADD COLUMN default_branch TEXT;
+    ALTER TABLE repos ADD COLUMN ahead_default INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN behind_default INTEGER NOT NULL DEFAULT 0;",
+    // 7: inputs and results of the health rules
+    "ALTER TABLE repos ADD COLUMN remote_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN last_commit_at TEXT;
+    ALTER TABLE repos ADD COLUMN branch_since TEXT;
+    ALTER TABLE repos ADD COLUMN severity TEXT NOT NULL DEFAULT 'ok';
+    ALTER TABLE repos ADD COLUMN health_reasons TEXT NOT NULL DEFAULT '[]';",
+    // 8: branch/remote/index condition flags
+    "ALTER TABLE repos ADD COLUMN detached INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN no_origin INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN no_upstream INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN upstream_gone INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN conflicted INTEGER NOT NULL DEFAULT 0;",
+    // 9: dirty file breakdown
+    "ALTER TABLE repos ADD COLUMN staged_files INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN unstaged_files INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN untracked_files INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN conflicted_files INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN ignored_large_files INTEGER NOT NULL DEFAULT 0;",
+    // 10: commit author, fetch and activity times
+    "ALTER TABLE repos ADD COLUMN last_commit_author TEXT;
+    ALTER TABLE repos ADD COLUMN last_fetched_at TEXT;
+    ALTER TABLE repos ADD COLUMN last_activity_at TEXT;",
+    // 11: metadata fingerprint for incremental rescans
+    "ALTER TABLE repos ADD COLUMN fingerprint TEXT;",
+    // 12: repo layout and parent repo
+    "ALTER TABLE repos ADD COLUMN kind TEXT NOT NULL DEFAULT 'standard';
+    ALTER TABLE repos ADD COLUMN parent_path TEXT;",
+    // 13: enclosing repo of a nested repo
+    "ALTER TABLE repos ADD COLUMN nested_in TEXT;",
+    // 14: submodule counts
+    "ALTER TABLE repos ADD COLUMN submodule_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN submodules_drifted INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE repos ADD COLUMN submodules_uninitialized INTEGER NOT NULL DEFAULT 0;",
+    // 15: repo tags, as carried by workspace manifests
+    "CREATE TABLE repo_tags (
+        repo_path TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (repo_path, tag)
+    );",
+];
+
+/// Apply any migrations newer than the database's recorded version.
+pub fn run(conn: &mut Connection) -> Result<(), AppError> {
+    let current: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let current = current as usize;
+
+    if current > MIGRATIONS.len() {
+        return Err(AppError::General(format!(
+            "Database schema version {} is newer than this build supports ({})",
+            current,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", (index + 1) as i64)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(conn: &Connection) -> usize {
+        conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
+            .unwrap() as usize
+    }
+
+    #[test]
+    fn fresh_database_reaches_the_latest_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn).unwrap();
+        assert_eq!(version(&conn), MIGRATIONS.len());
+
+        // Running again is a no-op rather than re-applying ALTERs
+        run(&mut conn).unwrap();
+        assert_eq!(version(&conn), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn upgrade_keeps_existing_rows_and_fills_defaults() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute("INSERT INTO repos (path, name) VALUES ('/work/cli', 'cli')", [])
+            .unwrap();
+
+        run(&mut conn).unwrap();
+        let (name, severity, kind, reasons): (String, String, String, String) = conn
+            .query_row(
+                "SELECT name, severity, kind, health_reasons FROM repos WHERE path = '/work/cli'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(name, "cli");
+        assert_eq!(severity, "ok");
+        assert_eq!(kind, "standard");
+        assert_eq!(reasons, "[]");
+    }
+
+    #[test]
+    fn newer_database_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", (MIGRATIONS.len() + 1) as i64)
+            .unwrap();
+        assert!(run(&mut conn).is_err());
+    }
+
+    #[test]
+    fn failed_migration_leaves_the_version_alone() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        // Migration 2 creates this table, so it fails after migration 1 commits
+        conn.execute_batch("CREATE TABLE repo_snapshots (id INTEGER);").unwrap();
+        assert!(run(&mut conn).is_err());
+        assert_eq!(version(&conn), 1);
+    }
+}
@@ -1,7 +1,9 @@
+mod migrations;
 pub mod models;
 pub mod queries;
 
 use rusqlite::Connection;
+use std::path::Path;
 use std::sync::Mutex;
 
 use crate::error::AppError;
@@ -11,37 +13,23 @@ pub struct Database {
 }
 
 impl Database {
-    pub fn new_in_memory() -> Result<Self, AppError> {
-        let conn = Connection::open_in_memory()?;
-        let db = Self {
-            conn: Mutex::new(conn),
-        };
-        db.init_schema()?;
-        Ok(db)
+    /// Open (or create) the database file at `path` and bring its schema up to date.
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        Self::from_connection(conn)
     }
 
-    fn init_schema(&self) -> Result<(), AppError> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS repos (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                path TEXT NOT NULL UNIQUE,
-                name TEXT NOT NULL,
-                branch TEXT NOT NULL DEFAULT 'unknown',
-                ahead INTEGER NOT NULL DEFAULT 0,
-                behind INTEGER NOT NULL DEFAULT 0,
-                dirty_files INTEGER NOT NULL DEFAULT 0,
-                stash_count INTEGER NOT NULL DEFAULT 0,
-                health TEXT NOT NULL DEFAULT 'clean',
-                last_checked TEXT NOT NULL DEFAULT '',
-                remote_url TEXT
-            );
+    pub fn new_in_memory() -> Result<Self, AppError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
 
-            CREATE TABLE IF NOT EXISTS scan_roots (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                path TEXT NOT NULL UNIQUE
-            );",
-        )?;
-        Ok(())
+    fn from_connection(mut conn: Connection) -> Result<Self, AppError> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrations::run(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
     }
 }
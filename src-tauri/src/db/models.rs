@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::error::ErrorCategory;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RepoInfo {
     pub id: Option<i64>,
     pub path: String,
@@ -13,17 +16,331 @@ pub struct RepoInfo {
     pub health: RepoHealth,
     pub last_checked: String,
     pub remote_url: Option<String>,
+    /// Files with changes staged in the index.
+    #[serde(default)]
+    pub staged_files: u32,
+    /// Tracked files modified or deleted in the working tree but not staged.
+    #[serde(default)]
+    pub unstaged_files: u32,
+    /// Untracked files; an untracked directory counts once.
+    #[serde(default)]
+    pub untracked_files: u32,
+    /// Files with unresolved merge conflicts.
+    #[serde(default)]
+    pub conflicted_files: u32,
+    /// Ignored files over 10 MiB (build artifacts, dumps) taking up space.
+    #[serde(default)]
+    pub ignored_large_files: u32,
+    /// The branch's configured upstream, e.g. `upstream/main`.
+    #[serde(default)]
+    pub upstream: Option<String>,
+    /// HEAD points at a commit rather than a branch; `branch` holds the short id.
+    #[serde(default)]
+    pub detached: bool,
+    /// There is no `origin` remote.
+    #[serde(default)]
+    pub no_origin: bool,
+    /// The checked-out branch has no upstream configured.
+    #[serde(default)]
+    pub no_upstream: bool,
+    /// The configured upstream branch no longer exists (deleted on the remote and pruned).
+    #[serde(default)]
+    pub upstream_gone: bool,
+    /// The index has unmerged entries.
+    #[serde(default)]
+    pub conflicted: bool,
+    /// The remote default branch, e.g. `origin/main`.
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Commits HEAD has that the default branch doesn't.
+    #[serde(default)]
+    pub ahead_default: u32,
+    /// Commits on the default branch missing from HEAD.
+    #[serde(default)]
+    pub behind_default: u32,
+    #[serde(default)]
+    pub remote_count: u32,
+    /// Commit time of HEAD (RFC 3339).
+    #[serde(default)]
+    pub last_commit_at: Option<String>,
+    /// Author name of the HEAD commit.
+    #[serde(default)]
+    pub last_commit_author: Option<String>,
+    /// When the repo was last fetched, from the modification time of `FETCH_HEAD`.
+    #[serde(default)]
+    pub last_fetched_at: Option<String>,
+    /// Time of the newest HEAD reflog entry: the last commit, checkout, reset, etc. made here.
+    #[serde(default)]
+    pub last_activity_at: Option<String>,
+    /// When the current branch was last checked out, from the HEAD reflog.
+    #[serde(default)]
+    pub branch_since: Option<String>,
+    /// Highest severity among the health rules this repo triggers.
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub health_reasons: Vec<HealthReason>,
+    /// Merge, rebase, etc. the repo was left in the middle of.
+    #[serde(default)]
+    pub in_progress: Option<InProgressOperation>,
+    /// Why the repo could not be read, when `health` is `Error`.
+    #[serde(default)]
+    pub error_detail: Option<String>,
+    /// How the repo is laid out on disk.
+    #[serde(default)]
+    pub kind: RepoKind,
+    /// For a linked worktree, the main repo's path; for a submodule, the superproject's.
+    #[serde(default)]
+    pub parent_path: Option<String>,
+    /// The repo whose working tree this one sits inside, if any.
+    #[serde(default)]
+    pub nested_in: Option<String>,
+    #[serde(default)]
+    pub submodule_count: u32,
+    /// Submodules checked out at a different commit than the one recorded.
+    #[serde(default)]
+    pub submodules_drifted: u32,
+    /// Submodules that aren't checked out.
+    #[serde(default)]
+    pub submodules_uninitialized: u32,
+    /// Git metadata fingerprint at the time status was computed; a rescan
+    /// skips repos whose fingerprint hasn't changed.
+    #[serde(skip)]
+    pub fingerprint: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
 pub enum RepoHealth {
+    #[default]
     Clean,
     Dirty,
     Diverged,
+    BehindTrunk,
+    InProgress,
     Error,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoKind {
+    /// A working tree with a `.git` directory.
+    #[default]
+    Standard,
+    /// A linked worktree (`git worktree add`) sharing another repo's objects and refs.
+    Worktree,
+    /// A submodule, whose git dir lives under the superproject's `.git/modules`.
+    Submodule,
+    /// A working tree whose `.git` file points at a git dir kept elsewhere
+    /// (`git init --separate-git-dir`).
+    SeparateGitDir,
+    /// A repository with no working tree.
+    Bare,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// A health rule that a repo triggered, shown on its card.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReason {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InProgressOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+/// Column to order a repo query by. Timestamp sorts put repos with no
+/// value last in either direction.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoSort {
+    #[default]
+    Name,
+    LastCommit,
+    LastFetch,
+    LastActivity,
+    LastChecked,
+}
+
+/// Sorting and filtering for the stored repo list. Time bounds are RFC 3339.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepoQuery {
+    pub sort: RepoSort,
+    pub descending: bool,
+    /// Only repos with reflog activity at or after this time.
+    pub active_since: Option<String>,
+    /// Only repos with no reflog activity since this time.
+    pub inactive_since: Option<String>,
+    /// Only repos not fetched since this time, including never-fetched ones.
+    pub fetched_before: Option<String>,
+    /// Only repos whose HEAD commit author contains this text.
+    pub author: Option<String>,
+}
+
+/// A point-in-time copy of a repo's status, recorded on every scan/fetch/refresh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    pub repo_path: String,
+    pub recorded_at: String,
+    pub branch: String,
+    pub ahead: u32,
+    pub behind: u32,
+    pub dirty_files: u32,
+    pub stash_count: u32,
+    pub health: RepoHealth,
+}
+
+/// How long each problem condition has held continuously, up to the latest snapshot.
+/// A `*_since` field is `None` when the condition does not currently hold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoTrend {
+    pub path: String,
+    pub name: String,
+    pub dirty_since: Option<String>,
+    pub dirty_days: Option<i64>,
+    pub ahead_since: Option<String>,
+    pub ahead_days: Option<i64>,
+    pub behind_since: Option<String>,
+    pub behind_days: Option<i64>,
+    pub diverged_since: Option<String>,
+    pub diverged_days: Option<i64>,
+    pub snapshot_count: u32,
+}
+
+/// What happened to a single repo during a bulk operation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OperationOutcome {
+    Success,
+    Skipped {
+        reason: String,
+    },
+    Failed {
+        category: ErrorCategory,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoOperationResult {
+    pub path: String,
+    pub name: String,
+    pub outcome: OperationOutcome,
+    pub duration_ms: u64,
+    /// Status after the operation ran.
+    pub repo: RepoInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct BulkSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub failed_by_category: BTreeMap<ErrorCategory, usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkReport {
+    pub operation: String,
+    pub summary: BulkSummary,
+    pub results: Vec<RepoOperationResult>,
+}
+
+/// The manifest formats `sync_manifest` understands.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestFormat {
+    /// The JSON manifest written by `export_manifest`.
+    Native,
+    /// A Google `repo` tool manifest (`default.xml`).
+    RepoXml,
+    /// One remote URL per line, optionally followed by a path.
+    UrlList,
+}
+
+/// What syncing found for a single manifest entry.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ManifestOutcome {
+    /// Already on disk and matching the manifest.
+    Present,
+    Cloned,
+    /// Not on disk, and cloning wasn't asked for.
+    Missing,
+    /// On disk, but with a different origin, remotes or default branch.
+    Mismatched {
+        reasons: Vec<String>,
+    },
+    /// Goes to the same directory as an earlier entry, which is synced
+    /// instead.
+    Duplicate,
+    Failed {
+        category: ErrorCategory,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntryResult {
+    /// Where the entry lives (or would live) on disk.
+    pub path: String,
+    pub url: Option<String>,
+    pub outcome: ManifestOutcome,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestSyncReport {
+    pub format: ManifestFormat,
+    pub root: String,
+    pub entries: Vec<ManifestEntryResult>,
+    /// Known repos under the root that the manifest doesn't list.
+    pub extra: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+}
+
+/// The result of one check run by `diagnose_repo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoDiagnostics {
+    pub path: String,
+    /// True when no check failed (warnings are allowed).
+    pub healthy: bool,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitInfo {
     pub oid: String,
@@ -90,6 +407,91 @@ pub struct CommitFileChange {
     pub status: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmoduleInfo {
+    pub name: String,
+    /// Path relative to the superproject's working tree.
+    pub path: String,
+    /// URL from `.gitmodules`.
+    pub url: Option<String>,
+    pub branch: Option<String>,
+    /// Commit the superproject records (in its index).
+    pub recorded_commit: Option<String>,
+    /// Commit the submodule's HEAD is at.
+    pub checked_out_commit: Option<String>,
+    /// URL copied into the superproject's config (`git submodule init`).
+    pub initialized: bool,
+    pub checked_out: bool,
+    /// `checked_out_commit` differs from `recorded_commit`.
+    pub drifted: bool,
+    /// Modified or untracked files inside the submodule.
+    pub dirty: bool,
+    /// Configured URLs differ from `.gitmodules` (`git submodule sync` needed).
+    pub out_of_sync: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeInfo {
+    /// `None` for the main working tree.
+    pub name: Option<String>,
+    pub path: String,
+    pub is_main: bool,
+    pub locked: bool,
+    pub lock_reason: Option<String>,
+    /// The working tree is gone; pruning removes what's left of it.
+    pub prunable: bool,
+    /// Status of the working tree, when it exists.
+    pub status: Option<RepoInfo>,
+}
+
+/// A local branch holding commits that may exist only on this machine.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnpushedBranch {
+    pub name: String,
+    /// The remote-tracking branch it follows, e.g. `origin/main`.
+    pub upstream: Option<String>,
+    /// An upstream is configured but its remote-tracking branch is gone.
+    pub upstream_gone: bool,
+    /// Commits ahead of the upstream or, without a usable upstream, commits
+    /// no remote-tracking branch contains.
+    pub unpushed_commits: usize,
+    pub last_commit_at: Option<String>,
+}
+
+/// Everything in one repo that would be lost with the clone.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UnpushedWork {
+    pub path: String,
+    pub name: String,
+    /// Branches with unpushed commits, and every branch without an upstream.
+    pub branches: Vec<UnpushedBranch>,
+    /// Commits on a detached HEAD that no branch, tag or remote-tracking
+    /// branch contains.
+    pub detached_commits: usize,
+    pub stashes: Vec<StashEntry>,
+    /// Tracked files with staged, unstaged or conflicted changes.
+    pub changed_files: Vec<String>,
+    /// Untracked files; an untracked directory is listed once with a trailing `/`.
+    pub untracked_files: Vec<String>,
+    /// Why the repo couldn't be read, leaving its local work unknown.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnpushedReport {
+    pub generated_at: String,
+    pub repos_checked: usize,
+    /// Repos with local-only work, or that couldn't be read, by path.
+    pub repos: Vec<UnpushedWork>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteInfo {
     pub name: String,
@@ -102,12 +504,46 @@ pub struct GitProfile {
     pub email: String,
 }
 
+impl BulkSummary {
+    pub fn from_results(results: &[RepoOperationResult]) -> Self {
+        let mut summary = Self {
+            total: results.len(),
+            ..Self::default()
+        };
+        for result in results {
+            match &result.outcome {
+                OperationOutcome::Success => summary.succeeded += 1,
+                OperationOutcome::Skipped { .. } => summary.skipped += 1,
+                OperationOutcome::Failed { category, .. } => {
+                    summary.failed += 1;
+                    *summary.failed_by_category.entry(*category).or_default() += 1;
+                }
+            }
+        }
+        summary
+    }
+}
+
+impl RepoSort {
+    pub fn column(&self) -> &'static str {
+        match self {
+            RepoSort::Name => "name",
+            RepoSort::LastCommit => "last_commit_at",
+            RepoSort::LastFetch => "last_fetched_at",
+            RepoSort::LastActivity => "last_activity_at",
+            RepoSort::LastChecked => "last_checked",
+        }
+    }
+}
+
 impl RepoHealth {
     pub fn as_str(&self) -> &'static str {
         match self {
             RepoHealth::Clean => "clean",
             RepoHealth::Dirty => "dirty",
             RepoHealth::Diverged => "diverged",
+            RepoHealth::BehindTrunk => "behind_trunk",
+            RepoHealth::InProgress => "in_progress",
             RepoHealth::Error => "error",
         }
     }
@@ -117,7 +553,88 @@ impl RepoHealth {
             "clean" => RepoHealth::Clean,
             "dirty" => RepoHealth::Dirty,
             "diverged" => RepoHealth::Diverged,
+            "behind_trunk" => RepoHealth::BehindTrunk,
+            "in_progress" => RepoHealth::InProgress,
             _ => RepoHealth::Error,
         }
     }
 }
+
+impl RepoKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepoKind::Standard => "standard",
+            RepoKind::Worktree => "worktree",
+            RepoKind::Submodule => "submodule",
+            RepoKind::SeparateGitDir => "separate_git_dir",
+            RepoKind::Bare => "bare",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "worktree" => RepoKind::Worktree,
+            "submodule" => RepoKind::Submodule,
+            "separate_git_dir" => RepoKind::SeparateGitDir,
+            "bare" => RepoKind::Bare,
+            _ => RepoKind::Standard,
+        }
+    }
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Ok => "ok",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "info" => Severity::Info,
+            "warning" => Severity::Warning,
+            "error" => Severity::Error,
+            _ => Severity::Ok,
+        }
+    }
+}
+
+impl InProgressOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InProgressOperation::Merge => "merge",
+            InProgressOperation::Rebase => "rebase",
+            InProgressOperation::CherryPick => "cherry_pick",
+            InProgressOperation::Revert => "revert",
+            InProgressOperation::Bisect => "bisect",
+            InProgressOperation::ApplyMailbox => "apply_mailbox",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "merge" => Some(InProgressOperation::Merge),
+            "rebase" => Some(InProgressOperation::Rebase),
+            "cherry_pick" => Some(InProgressOperation::CherryPick),
+            "revert" => Some(InProgressOperation::Revert),
+            "bisect" => Some(InProgressOperation::Bisect),
+            "apply_mailbox" => Some(InProgressOperation::ApplyMailbox),
+            _ => None,
+        }
+    }
+
+    /// How git itself names the operation, for messages.
+    pub fn label(&self) -> &'static str {
+        match self {
+            InProgressOperation::Merge => "merge",
+            InProgressOperation::Rebase => "rebase",
+            InProgressOperation::CherryPick => "cherry-pick",
+            InProgressOperation::Revert => "revert",
+            InProgressOperation::Bisect => "bisect",
+            InProgressOperation::ApplyMailbox => "am",
+        }
+    }
+}
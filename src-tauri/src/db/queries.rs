@@ -1,48 +1,103 @@
-use rusqlite::params;
+use std::collections::HashMap;
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 
 use super::Database;
-use crate::db::models::{RepoHealth, RepoInfo};
+use crate::db::models::{
+    InProgressOperation, RepoHealth, RepoInfo, RepoKind, RepoQuery, RepoSort, RepoTrend,
+    Severity, StatusSnapshot,
+};
 use crate::error::AppError;
 
 impl Database {
     pub fn upsert_repo(&self, repo: &RepoInfo) -> Result<(), AppError> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT INTO repos (path, name, branch, ahead, behind, dirty_files, stash_count, health, last_checked, remote_url)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-             ON CONFLICT(path) DO UPDATE SET
-                name = excluded.name,
-                branch = excluded.branch,
-                ahead = excluded.ahead,
-                behind = excluded.behind,
-                dirty_files = excluded.dirty_files,
-                stash_count = excluded.stash_count,
-                health = excluded.health,
-                last_checked = excluded.last_checked,
-                remote_url = excluded.remote_url",
-            params![
-                repo.path,
-                repo.name,
-                repo.branch,
-                repo.ahead,
-                repo.behind,
-                repo.dirty_files,
-                repo.stash_count,
-                repo.health.as_str(),
-                repo.last_checked,
-                repo.remote_url,
-            ],
-        )?;
+        upsert(&conn, repo)
+    }
+
+    /// Upsert many repos in a single transaction.
+    pub fn upsert_repos(&self, repos: &[RepoInfo]) -> Result<(), AppError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for repo in repos {
+            upsert(&tx, repo)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete the repos at `paths` in a single transaction. Their status
+    /// snapshots are kept until pruned.
+    pub fn remove_repos(&self, paths: &[String]) -> Result<(), AppError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for path in paths {
+            tx.execute("DELETE FROM repos WHERE path = ?1", params![path])?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
     pub fn get_all_repos(&self) -> Result<Vec<RepoInfo>, AppError> {
+        self.query_repos(&RepoQuery::default())
+    }
+
+    /// Stored repos filtered and ordered by `query`. Ties (and the default
+    /// order) fall back to the repo name.
+    pub fn query_repos(&self, query: &RepoQuery) -> Result<Vec<RepoInfo>, AppError> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<&str> = Vec::new();
+        if let Some(since) = &query.active_since {
+            values.push(since);
+            conditions.push(format!("last_activity_at >= ?{}", values.len()));
+        }
+        if let Some(since) = &query.inactive_since {
+            values.push(since);
+            conditions.push(format!(
+                "(last_activity_at IS NULL OR last_activity_at < ?{})",
+                values.len()
+            ));
+        }
+        if let Some(before) = &query.fetched_before {
+            values.push(before);
+            conditions.push(format!(
+                "(last_fetched_at IS NULL OR last_fetched_at < ?{})",
+                values.len()
+            ));
+        }
+        if let Some(author) = &query.author {
+            values.push(author);
+            conditions.push(format!(
+                "instr(lower(last_commit_author), lower(?{})) > 0",
+                values.len()
+            ));
+        }
+
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let direction = if query.descending { "DESC" } else { "ASC" };
+        let order = match query.sort {
+            RepoSort::Name => format!("name {}", direction),
+            sort => format!("{0} IS NULL, {0} {1}, name", sort.column(), direction),
+        };
+
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, path, name, branch, ahead, behind, dirty_files, stash_count, health, last_checked, remote_url FROM repos ORDER BY name",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, path, name, branch, ahead, behind, dirty_files, stash_count, health, last_checked, remote_url, error_detail, in_progress, upstream, default_branch, ahead_default, behind_default,
+                    remote_count, last_commit_at, branch_since, severity, health_reasons,
+                    detached, no_origin, no_upstream, upstream_gone, conflicted,
+                    staged_files, unstaged_files, untracked_files, conflicted_files, ignored_large_files,
+                    last_commit_author, last_fetched_at, last_activity_at, fingerprint,
+                    kind, parent_path, nested_in,
+                    submodule_count, submodules_drifted, submodules_uninitialized
+             FROM repos {} ORDER BY {}",
+            filter, order
+        ))?;
         let repos = stmt
-            .query_map([], |row| {
+            .query_map(params_from_iter(values), |row| {
                 Ok(RepoInfo {
                     id: row.get(0)?,
                     path: row.get(1)?,
@@ -55,15 +110,315 @@ impl Database {
                     health: RepoHealth::from_str(&row.get::<_, String>(8)?),
                     last_checked: row.get(9)?,
                     remote_url: row.get(10)?,
+                    error_detail: row.get(11)?,
+                    in_progress: row
+                        .get::<_, Option<String>>(12)?
+                        .and_then(|s| InProgressOperation::from_str(&s)),
+                    upstream: row.get(13)?,
+                    default_branch: row.get(14)?,
+                    ahead_default: row.get(15)?,
+                    behind_default: row.get(16)?,
+                    remote_count: row.get(17)?,
+                    last_commit_at: row.get(18)?,
+                    branch_since: row.get(19)?,
+                    severity: Severity::from_str(&row.get::<_, String>(20)?),
+                    health_reasons: serde_json::from_str(&row.get::<_, String>(21)?)
+                        .unwrap_or_default(),
+                    detached: row.get(22)?,
+                    no_origin: row.get(23)?,
+                    no_upstream: row.get(24)?,
+                    upstream_gone: row.get(25)?,
+                    conflicted: row.get(26)?,
+                    staged_files: row.get(27)?,
+                    unstaged_files: row.get(28)?,
+                    untracked_files: row.get(29)?,
+                    conflicted_files: row.get(30)?,
+                    ignored_large_files: row.get(31)?,
+                    last_commit_author: row.get(32)?,
+                    last_fetched_at: row.get(33)?,
+                    last_activity_at: row.get(34)?,
+                    fingerprint: row.get(35)?,
+                    kind: RepoKind::from_str(&row.get::<_, String>(36)?),
+                    parent_path: row.get(37)?,
+                    nested_in: row.get(38)?,
+                    submodule_count: row.get(39)?,
+                    submodules_drifted: row.get(40)?,
+                    submodules_uninitialized: row.get(41)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(repos)
     }
 
-    pub fn clear_repos(&self) -> Result<(), AppError> {
+    /// The last measured `ignored_large_files` of the repo at `path`, 0 for
+    /// an unknown repo.
+    pub fn get_ignored_large_files(&self, path: &str) -> Result<u32, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let count = conn
+            .query_row(
+                "SELECT ignored_large_files FROM repos WHERE path = ?1",
+                params![path],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(count.unwrap_or(0))
+    }
+
+    // ── Tags ──
+
+    /// Every repo's tags, keyed by repo path. Tags outlive their repo like
+    /// snapshots do, so a repo that drops out of a scan keeps them.
+    pub fn get_repo_tags(&self) -> Result<HashMap<String, Vec<String>>, AppError> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM repos", [])?;
+        let mut stmt = conn.prepare("SELECT repo_path, tag FROM repo_tags ORDER BY repo_path, tag")?;
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (path, tag) = row?;
+            tags.entry(path).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
+    /// Replace the tags of the repo at `path`.
+    pub fn set_repo_tags(&self, path: &str, tags: &[String]) -> Result<(), AppError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM repo_tags WHERE repo_path = ?1", params![path])?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO repo_tags (repo_path, tag) VALUES (?1, ?2)",
+                params![path, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // ── Status snapshots ──
+
+    /// Append a status snapshot for each repo, all stamped with the same time.
+    pub fn record_snapshots(&self, repos: &[RepoInfo]) -> Result<(), AppError> {
+        let recorded_at = chrono::Utc::now().to_rfc3339();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO repo_snapshots (repo_path, recorded_at, branch, ahead, behind, dirty_files, stash_count, health)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for repo in repos {
+                stmt.execute(params![
+                    repo.path,
+                    recorded_at,
+                    repo.branch,
+                    repo.ahead,
+                    repo.behind,
+                    repo.dirty_files,
+                    repo.stash_count,
+                    repo.health.as_str(),
+                ])?;
+            }
+        }
+        tx.commit()?;
         Ok(())
     }
+
+    /// Snapshots for one repo recorded at or after `since` (RFC 3339), oldest first.
+    pub fn get_status_history(
+        &self,
+        path: &str,
+        since: &str,
+    ) -> Result<Vec<StatusSnapshot>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT repo_path, recorded_at, branch, ahead, behind, dirty_files, stash_count, health
+             FROM repo_snapshots
+             WHERE repo_path = ?1 AND recorded_at >= ?2
+             ORDER BY recorded_at",
+        )?;
+        let snapshots = stmt
+            .query_map(params![path, since], |row| {
+                Ok(StatusSnapshot {
+                    repo_path: row.get(0)?,
+                    recorded_at: row.get(1)?,
+                    branch: row.get(2)?,
+                    ahead: row.get(3)?,
+                    behind: row.get(4)?,
+                    dirty_files: row.get(5)?,
+                    stash_count: row.get(6)?,
+                    health: RepoHealth::from_str(&row.get::<_, String>(7)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(snapshots)
+    }
+
+    /// For every known repo, find when each problem condition (dirty, unpushed,
+    /// behind, diverged) started, i.e. the first snapshot after the most recent
+    /// snapshot in which the condition did not hold.
+    pub fn get_repo_trends(&self) -> Result<Vec<RepoTrend>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT r.path, r.name,
+                (SELECT MIN(s.recorded_at) FROM repo_snapshots s
+                  WHERE s.repo_path = r.path AND s.recorded_at > COALESCE(
+                    (SELECT MAX(c.recorded_at) FROM repo_snapshots c
+                      WHERE c.repo_path = r.path AND c.dirty_files = 0), '')),
+                (SELECT MIN(s.recorded_at) FROM repo_snapshots s
+                  WHERE s.repo_path = r.path AND s.recorded_at > COALESCE(
+                    (SELECT MAX(c.recorded_at) FROM repo_snapshots c
+                      WHERE c.repo_path = r.path AND c.ahead = 0), '')),
+                (SELECT MIN(s.recorded_at) FROM repo_snapshots s
+                  WHERE s.repo_path = r.path AND s.recorded_at > COALESCE(
+                    (SELECT MAX(c.recorded_at) FROM repo_snapshots c
+                      WHERE c.repo_path = r.path AND c.behind = 0), '')),
+                (SELECT MIN(s.recorded_at) FROM repo_snapshots s
+                  WHERE s.repo_path = r.path AND s.recorded_at > COALESCE(
+                    (SELECT MAX(c.recorded_at) FROM repo_snapshots c
+                      WHERE c.repo_path = r.path AND c.health != 'diverged'), '')),
+                (SELECT COUNT(*) FROM repo_snapshots s WHERE s.repo_path = r.path)
+             FROM repos r
+             ORDER BY r.name",
+        )?;
+        let now = chrono::Utc::now();
+        let days_since = |since: &Option<String>| {
+            since
+                .as_deref()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                .map(|t| (now - t.with_timezone(&chrono::Utc)).num_days())
+        };
+        let trends = stmt
+            .query_map([], |row| {
+                let dirty_since: Option<String> = row.get(2)?;
+                let ahead_since: Option<String> = row.get(3)?;
+                let behind_since: Option<String> = row.get(4)?;
+                let diverged_since: Option<String> = row.get(5)?;
+                Ok(RepoTrend {
+                    path: row.get(0)?,
+                    name: row.get(1)?,
+                    dirty_days: days_since(&dirty_since),
+                    dirty_since,
+                    ahead_days: days_since(&ahead_since),
+                    ahead_since,
+                    behind_days: days_since(&behind_since),
+                    behind_since,
+                    diverged_days: days_since(&diverged_since),
+                    diverged_since,
+                    snapshot_count: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(trends)
+    }
+
+    /// Delete snapshots recorded before `before` (RFC 3339).
+    pub fn prune_snapshots(&self, before: &str) -> Result<usize, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn.execute(
+            "DELETE FROM repo_snapshots WHERE recorded_at < ?1",
+            params![before],
+        )?;
+        Ok(deleted)
+    }
+}
+
+fn upsert(conn: &Connection, repo: &RepoInfo) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO repos (path, name, branch, ahead, behind, dirty_files, stash_count, health, last_checked, remote_url, error_detail, in_progress, upstream, default_branch, ahead_default, behind_default,
+                            remote_count, last_commit_at, branch_since, severity, health_reasons,
+                            detached, no_origin, no_upstream, upstream_gone, conflicted,
+                            staged_files, unstaged_files, untracked_files, conflicted_files, ignored_large_files,
+                            last_commit_author, last_fetched_at, last_activity_at, fingerprint,
+                            kind, parent_path, nested_in,
+                            submodule_count, submodules_drifted, submodules_uninitialized)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21,
+                 ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35,
+                 ?36, ?37, ?38, ?39, ?40, ?41)
+         ON CONFLICT(path) DO UPDATE SET
+            name = excluded.name,
+            branch = excluded.branch,
+            ahead = excluded.ahead,
+            behind = excluded.behind,
+            dirty_files = excluded.dirty_files,
+            stash_count = excluded.stash_count,
+            health = excluded.health,
+            last_checked = excluded.last_checked,
+            remote_url = excluded.remote_url,
+            error_detail = excluded.error_detail,
+            in_progress = excluded.in_progress,
+            upstream = excluded.upstream,
+            default_branch = excluded.default_branch,
+            ahead_default = excluded.ahead_default,
+            behind_default = excluded.behind_default,
+            remote_count = excluded.remote_count,
+            last_commit_at = excluded.last_commit_at,
+            branch_since = excluded.branch_since,
+            severity = excluded.severity,
+            health_reasons = excluded.health_reasons,
+            detached = excluded.detached,
+            no_origin = excluded.no_origin,
+            no_upstream = excluded.no_upstream,
+            upstream_gone = excluded.upstream_gone,
+            conflicted = excluded.conflicted,
+            staged_files = excluded.staged_files,
+            unstaged_files = excluded.unstaged_files,
+            untracked_files = excluded.untracked_files,
+            conflicted_files = excluded.conflicted_files,
+            ignored_large_files = excluded.ignored_large_files,
+            last_commit_author = excluded.last_commit_author,
+            last_fetched_at = excluded.last_fetched_at,
+            last_activity_at = excluded.last_activity_at,
+            fingerprint = excluded.fingerprint,
+            kind = excluded.kind,
+            parent_path = excluded.parent_path,
+            nested_in = excluded.nested_in,
+            submodule_count = excluded.submodule_count,
+            submodules_drifted = excluded.submodules_drifted,
+            submodules_uninitialized = excluded.submodules_uninitialized",
+        params![
+            repo.path,
+            repo.name,
+            repo.branch,
+            repo.ahead,
+            repo.behind,
+            repo.dirty_files,
+            repo.stash_count,
+            repo.health.as_str(),
+            repo.last_checked,
+            repo.remote_url,
+            repo.error_detail,
+            repo.in_progress.map(|op| op.as_str()),
+            repo.upstream,
+            repo.default_branch,
+            repo.ahead_default,
+            repo.behind_default,
+            repo.remote_count,
+            repo.last_commit_at,
+            repo.branch_since,
+            repo.severity.as_str(),
+            serde_json::to_string(&repo.health_reasons).unwrap_or_else(|_| "[]".to_string()),
+            repo.detached,
+            repo.no_origin,
+            repo.no_upstream,
+            repo.upstream_gone,
+            repo.conflicted,
+            repo.staged_files,
+            repo.unstaged_files,
+            repo.untracked_files,
+            repo.conflicted_files,
+            repo.ignored_large_files,
+            repo.last_commit_author,
+            repo.last_fetched_at,
+            repo.last_activity_at,
+            repo.fingerprint,
+            repo.kind.as_str(),
+            repo.parent_path,
+            repo.nested_in,
+            repo.submodule_count,
+            repo.submodules_drifted,
+            repo.submodules_uninitialized,
+        ],
+    )?;
+    Ok(())
 }
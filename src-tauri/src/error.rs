@@ -1,3 +1,4 @@
+use serde::ser::SerializeStruct;
 use serde::Serialize;
 
 #[derive(Debug, thiserror::Error)]
@@ -11,15 +12,247 @@ pub enum AppError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("HEAD is not on a branch")]
+    DetachedHead,
+
+    #[error("No upstream branch found for {0}")]
+    NoUpstream(String),
+
+    #[error("No remotes configured")]
+    NoRemotes,
+
+    #[error("No '{0}' remote found")]
+    RemoteNotFound(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("A {0} is in progress")]
+    InProgress(String),
+
+    #[error("{0}")]
+    Unsupported(String),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
     #[error("{0}")]
     General(String),
+
+    /// Another error annotated with the repository it happened in.
+    #[error("{source}")]
+    InRepo {
+        path: String,
+        source: Box<AppError>,
+    },
 }
 
+/// Coarse, stable classification of an error for reporting and grouping.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Auth,
+    Network,
+    Conflict,
+    NoUpstream,
+    NoRemote,
+    DetachedHead,
+    NotFound,
+    Locked,
+    Permission,
+    Rejected,
+    Cancelled,
+    Database,
+    Io,
+    Other,
+}
+
+impl AppError {
+    /// Attach the repository path to this error (keeping an existing one).
+    pub fn in_repo(self, path: &str) -> Self {
+        match self {
+            e @ AppError::InRepo { .. } => e,
+            e => AppError::InRepo {
+                path: path.to_string(),
+                source: Box::new(e),
+            },
+        }
+    }
+
+    pub fn repo_path(&self) -> Option<&str> {
+        match self {
+            AppError::InRepo { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            AppError::Git(e) => git_category(e),
+            AppError::Database(_) => ErrorCategory::Database,
+            AppError::Io(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                ErrorCategory::Permission
+            }
+            AppError::Io(e) if e.kind() == std::io::ErrorKind::NotFound => ErrorCategory::NotFound,
+            AppError::Io(_) => ErrorCategory::Io,
+            AppError::DetachedHead => ErrorCategory::DetachedHead,
+            AppError::NoUpstream(_) => ErrorCategory::NoUpstream,
+            AppError::NoRemotes | AppError::RemoteNotFound(_) => ErrorCategory::NoRemote,
+            AppError::Conflict(_) | AppError::InProgress(_) => ErrorCategory::Conflict,
+            AppError::Cancelled => ErrorCategory::Cancelled,
+            AppError::Unsupported(_) | AppError::General(_) => ErrorCategory::Other,
+            AppError::InRepo { source, .. } => source.category(),
+        }
+    }
+
+    /// Stable, machine-readable code. Finer-grained than the category;
+    /// the frontend keys targeted fixes off it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Git(e) => git_code(e),
+            AppError::Database(_) => "database_error",
+            AppError::Io(e) => match e.kind() {
+                std::io::ErrorKind::PermissionDenied => "permission_denied",
+                std::io::ErrorKind::NotFound => "path_not_found",
+                _ => "io_error",
+            },
+            AppError::DetachedHead => "detached_head",
+            AppError::NoUpstream(_) => "no_upstream",
+            AppError::NoRemotes => "no_remotes",
+            AppError::RemoteNotFound(_) => "remote_not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::InProgress(_) => "operation_in_progress",
+            AppError::Unsupported(_) => "unsupported",
+            AppError::Cancelled => "cancelled",
+            AppError::General(_) => "general",
+            AppError::InRepo { source, .. } => source.code(),
+        }
+    }
+
+    /// A short suggestion for how the user can fix the problem, if there is one.
+    pub fn hint(&self) -> Option<&'static str> {
+        let hint = match self.code() {
+            "auth_failed" => "Check your credentials, or unlock your SSH key with `ssh-add`",
+            "ssh_key_locked" => "Unlock your SSH key with `ssh-add` and try again",
+            "certificate_invalid" => "The server's TLS/SSH certificate could not be verified",
+            "network_error" | "timeout" => "Check your network connection or VPN",
+            "no_upstream" => "Set an upstream with `git branch --set-upstream-to=<remote>/<branch>`",
+            "no_remotes" | "remote_not_found" => "Add a remote for this repository",
+            "detached_head" => "Check out a branch first",
+            "not_fast_forward" => "Pull (rebase) before pushing",
+            "merge_conflict" | "unmerged_entries" | "conflict" => {
+                "Resolve the conflicts, then continue or abort the operation"
+            }
+            "operation_in_progress" => "Continue or abort the operation in progress first",
+            "uncommitted_changes" => "Commit or stash your local changes first",
+            "locked" => "Another git process may be running; otherwise remove the stale .lock file",
+            "permission_denied" => "Check the file permissions of the repository",
+            "unborn_branch" => "The branch has no commits yet",
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
+fn git_category(e: &git2::Error) -> ErrorCategory {
+    use git2::{ErrorClass, ErrorCode};
+
+    match e.code() {
+        ErrorCode::Auth | ErrorCode::Certificate => return ErrorCategory::Auth,
+        ErrorCode::Conflict
+        | ErrorCode::MergeConflict
+        | ErrorCode::Unmerged
+        | ErrorCode::Uncommitted => return ErrorCategory::Conflict,
+        ErrorCode::Locked => return ErrorCategory::Locked,
+        ErrorCode::NotFound => return ErrorCategory::NotFound,
+        ErrorCode::NotFastForward => return ErrorCategory::Rejected,
+        ErrorCode::Timeout => return ErrorCategory::Network,
+        ErrorCode::User => return ErrorCategory::Cancelled,
+        _ => {}
+    }
+
+    match e.class() {
+        ErrorClass::Ssh if is_ssh_auth_failure(e) => ErrorCategory::Auth,
+        ErrorClass::Net | ErrorClass::Ssh | ErrorClass::Http | ErrorClass::Ssl => {
+            ErrorCategory::Network
+        }
+        ErrorClass::Merge | ErrorClass::Rebase | ErrorClass::Checkout => ErrorCategory::Conflict,
+        ErrorClass::Os if e.message().to_lowercase().contains("permission denied") => {
+            ErrorCategory::Permission
+        }
+        _ => ErrorCategory::Other,
+    }
+}
+
+fn git_code(e: &git2::Error) -> &'static str {
+    use git2::{ErrorClass, ErrorCode};
+
+    match e.code() {
+        ErrorCode::Auth if is_ssh_key_locked(e) => "ssh_key_locked",
+        ErrorCode::Auth => "auth_failed",
+        ErrorCode::Certificate => "certificate_invalid",
+        ErrorCode::Conflict | ErrorCode::MergeConflict => "merge_conflict",
+        ErrorCode::Unmerged => "unmerged_entries",
+        ErrorCode::Uncommitted => "uncommitted_changes",
+        ErrorCode::Locked => "locked",
+        ErrorCode::NotFound => "not_found",
+        ErrorCode::Exists => "already_exists",
+        ErrorCode::Ambiguous => "ambiguous",
+        ErrorCode::BareRepo => "bare_repo",
+        ErrorCode::UnbornBranch => "unborn_branch",
+        ErrorCode::NotFastForward => "not_fast_forward",
+        ErrorCode::InvalidSpec => "invalid_spec",
+        ErrorCode::Timeout => "timeout",
+        ErrorCode::User => "cancelled",
+        _ => match e.class() {
+            ErrorClass::Ssh if is_ssh_key_locked(e) => "ssh_key_locked",
+            ErrorClass::Ssh if is_ssh_auth_failure(e) => "auth_failed",
+            ErrorClass::Net | ErrorClass::Ssh | ErrorClass::Http | ErrorClass::Ssl => {
+                "network_error"
+            }
+            ErrorClass::Os if e.message().to_lowercase().contains("permission denied") => {
+                "permission_denied"
+            }
+            _ => "git_error",
+        },
+    }
+}
+
+// libgit2 reports most SSH auth failures as generic SSH errors, so fall back
+// to the message text.
+fn is_ssh_auth_failure(e: &git2::Error) -> bool {
+    let msg = e.message().to_lowercase();
+    msg.contains("auth") || msg.contains("publickey")
+}
+
+fn is_ssh_key_locked(e: &git2::Error) -> bool {
+    let msg = e.message().to_lowercase();
+    msg.contains("passphrase") || msg.contains("agent")
+}
+
+/// Serializes as `{code, category, message, repo_path, hint}` for the frontend.
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let mut s = serializer.serialize_struct("AppError", 5)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("category", &self.category())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.serialize_field("repo_path", &self.repo_path())?;
+        s.serialize_field("hint", &self.hint())?;
+        s.end()
+    }
+}
+
+/// Attach a repository path to the error of a `Result`.
+pub trait ResultExt<T> {
+    fn in_repo(self, path: &str) -> Result<T, AppError>;
+}
+
+impl<T> ResultExt<T> for Result<T, AppError> {
+    fn in_repo(self, path: &str) -> Result<T, AppError> {
+        self.map_err(|e| e.in_repo(path))
     }
 }
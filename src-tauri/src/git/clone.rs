@@ -0,0 +1,244 @@
+use git2::build::RepoBuilder;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::operations::{cancelled_or, make_fetch_options};
+use super::progress::OpContext;
+use crate::error::AppError;
+
+/// Where clones go when the config doesn't say otherwise.
+pub const DEFAULT_LAYOUT: &str = "{root}/{host}/{owner}/{repo}";
+
+/// The parts of a remote URL a clone layout can use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl {
+    pub host: String,
+    /// Everything between host and repo name; nested for GitLab-style
+    /// subgroups (`group/subgroup`), empty when the repo sits at the top.
+    pub owner: String,
+    pub repo: String,
+}
+
+impl RemoteUrl {
+    /// Parse `scheme://[user@]host[:port]/owner/repo[.git]` or scp-like
+    /// `[user@]host:owner/repo[.git]`. Local paths, `file://` URLs and
+    /// Windows paths such as `C:\src\repo` aren't remote URLs.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (authority, path) = match url.split_once("://") {
+            Some(("file", _)) => return None,
+            Some((_, rest)) => rest.split_once('/')?,
+            None => {
+                let (authority, path) = url.split_once(':')?;
+                // A slash before the colon, or a drive letter, means a local path
+                if authority.contains(['/', '\\']) || authority.len() == 1 {
+                    return None;
+                }
+                (authority, path)
+            }
+        };
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?.to_lowercase();
+
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let (owner, repo) = path.rsplit_once('/').unwrap_or(("", path));
+        if host.is_empty() || repo.is_empty() {
+            return None;
+        }
+        Some(RemoteUrl {
+            host,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+}
+
+/// Expand a layout template such as `{root}/{host}/{owner}/{repo}` for `url`.
+/// The template must start with `{root}`, and the result must stay inside it.
+pub fn destination(layout: &str, root: &Path, url: &str) -> Result<PathBuf, AppError> {
+    let remote = RemoteUrl::parse(url)
+        .ok_or_else(|| AppError::General(format!("Not a remote repository URL: {}", url)))?;
+    let rel = layout.strip_prefix("{root}").ok_or_else(|| {
+        AppError::General(format!("Clone layout '{}' must start with {{root}}", layout))
+    })?;
+    let rel = rel
+        .replace("{host}", &remote.host)
+        .replace("{owner}", &remote.owner)
+        .replace("{repo}", &remote.repo);
+
+    let mut dest = root.to_path_buf();
+    // Empty segments come from an empty owner; skip them rather than nest a blank dir
+    for part in rel.split('/').filter(|part| !part.is_empty()) {
+        if part == "." || part == ".." {
+            return Err(AppError::General(format!(
+                "Clone layout '{}' leaves the scan root for {}",
+                layout, url
+            )));
+        }
+        dest.push(part);
+    }
+    if dest == root {
+        return Err(AppError::General(format!(
+            "Clone layout '{}' doesn't name a directory for the repo",
+            layout
+        )));
+    }
+    Ok(dest)
+}
+
+/// Clone `url` into `dest`, with the same credentials, progress and
+/// cancellation as a fetch. A failed or cancelled clone leaves behind only
+/// what was there before it.
+pub fn clone_repo(url: &str, dest: &Path, ctx: &OpContext) -> Result<(), AppError> {
+    ctx.cancel.check()?;
+    let existed = dest.exists();
+    if existed && dest.read_dir()?.next().is_some() {
+        return Err(AppError::General(format!(
+            "{} already exists and is not empty",
+            dest.display()
+        )));
+    }
+    // Deepest first, for removing them again if the clone fails
+    let created: Vec<PathBuf> = dest
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+        .map(Path::to_path_buf)
+        .collect();
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut builder = RepoBuilder::new();
+    builder.fetch_options(make_fetch_options(dest, "origin", ctx));
+    if let Err(e) = builder.clone(url, dest) {
+        clean_up(dest, existed, &created);
+        return Err(cancelled_or(ctx, e));
+    }
+    Ok(())
+}
+
+/// Undo a failed clone: empty `dest` if it was there before, else remove
+/// it, then remove the parents `created` for it. Those go only while empty,
+/// since another clone may be landing beside this one.
+fn clean_up(dest: &Path, existed: bool, created: &[PathBuf]) {
+    if !existed {
+        let _ = fs::remove_dir_all(dest);
+    } else if let Ok(entries) = fs::read_dir(dest) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let _ = match entry.file_type() {
+                Ok(kind) if kind.is_dir() => fs::remove_dir_all(path),
+                _ => fs::remove_file(path),
+            };
+        }
+    }
+    for dir in created {
+        if fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(host: &str, owner: &str, repo: &str) -> Option<RemoteUrl> {
+        Some(RemoteUrl {
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+
+    #[test]
+    fn clean_up_removes_only_what_the_clone_created() {
+        let base = std::env::temp_dir().join(format!("gitatlas-clone-clean-up-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+
+        // An empty destination that was already there stays, emptied
+        let dest = base.join("existing");
+        fs::create_dir_all(dest.join(".git")).unwrap();
+        fs::write(dest.join("partial"), b"x").unwrap();
+        clean_up(&dest, true, &[]);
+        assert!(dest.is_dir());
+        assert_eq!(fs::read_dir(&dest).unwrap().count(), 0);
+
+        // New parents go, unless a sibling clone is using them
+        let host = base.join("host");
+        let dest = host.join("owner/repo");
+        fs::create_dir_all(dest.join(".git")).unwrap();
+        fs::create_dir_all(host.join("other/repo")).unwrap();
+        clean_up(&dest, false, &[host.join("owner"), host.clone()]);
+        assert!(!host.join("owner").exists());
+        assert!(host.join("other/repo").is_dir());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn parses_url_and_scp_forms_alike() {
+        let expected = remote("github.com", "acme", "cli");
+        assert_eq!(RemoteUrl::parse("https://github.com/acme/cli.git"), expected);
+        assert_eq!(RemoteUrl::parse("git@github.com:acme/cli.git"), expected);
+        assert_eq!(RemoteUrl::parse("ssh://git@GitHub.com:22/acme/cli/"), expected);
+    }
+
+    #[test]
+    fn keeps_nested_owners_and_allows_none() {
+        assert_eq!(
+            RemoteUrl::parse("https://gitlab.com/group/sub/project.git"),
+            remote("gitlab.com", "group/sub", "project")
+        );
+        assert_eq!(RemoteUrl::parse("https://example.com/project"), remote("example.com", "", "project"));
+    }
+
+    #[test]
+    fn local_paths_are_not_remote_urls() {
+        assert_eq!(RemoteUrl::parse("/srv/git/cli.git"), None);
+        assert_eq!(RemoteUrl::parse("../cli"), None);
+        assert_eq!(RemoteUrl::parse("file:///srv/git/cli.git"), None);
+        assert_eq!(RemoteUrl::parse("file://server/git/cli.git"), None);
+        assert_eq!(RemoteUrl::parse(r"C:\src\repo"), None);
+        assert_eq!(RemoteUrl::parse("C:/src/repo"), None);
+        assert_eq!(RemoteUrl::parse(r"src\sub:repo"), None);
+        assert_eq!(RemoteUrl::parse("https://github.com/"), None);
+    }
+
+    #[test]
+    fn destination_expands_the_layout() {
+        let root = Path::new("/work");
+        assert_eq!(
+            destination(DEFAULT_LAYOUT, root, "git@github.com:acme/cli.git").unwrap(),
+            root.join("github.com/acme/cli")
+        );
+        // An empty owner doesn't leave a blank directory behind
+        assert_eq!(
+            destination(DEFAULT_LAYOUT, root, "https://example.com/cli").unwrap(),
+            root.join("example.com/cli")
+        );
+        assert_eq!(
+            destination("{root}/{repo}", root, "https://github.com/acme/cli").unwrap(),
+            root.join("cli")
+        );
+    }
+
+    #[test]
+    fn destination_rejects_dot_segments() {
+        let root = Path::new("/work");
+        assert!(destination(DEFAULT_LAYOUT, root, "https://github.com/acme/..").is_err());
+        assert!(destination("{root}/{owner}/{repo}", root, "https://github.com/../cli").is_err());
+        assert!(destination("{root}/../{repo}", root, "https://github.com/acme/cli").is_err());
+        assert!(destination("{root}/./{repo}", root, "https://github.com/acme/cli").is_err());
+    }
+
+    #[test]
+    fn destination_needs_root_and_a_directory() {
+        let root = Path::new("/work");
+        assert!(destination("/elsewhere/{repo}", root, "https://github.com/acme/cli").is_err());
+        assert!(destination("{root}", root, "https://github.com/acme/cli").is_err());
+        assert!(destination(DEFAULT_LAYOUT, root, "/srv/git/cli").is_err());
+    }
+}
@@ -548,7 +548,7 @@ pub fn merge_branch(path: &Path, branch_name: &str) -> Result<String, AppError>
     let mut index = repo.index()?;
     if index.has_conflicts() {
         repo.cleanup_state()?;
-        return Err(AppError::General("Merge has conflicts — resolve them manually".to_string()));
+        return Err(AppError::Conflict("Merge has conflicts — resolve them manually".to_string()));
     }
     let tree_oid = index.write_tree()?;
     let tree = repo.find_tree(tree_oid)?;
@@ -742,20 +742,22 @@ pub fn squash_commits(path: &Path, count: usize, message: &str) -> Result<String
 
 pub fn get_pr_url(path: &Path) -> Result<String, AppError> {
     let repo = Repository::open(path)?;
-    let remote = repo.find_remote("origin").map_err(|_| {
-        AppError::General("No 'origin' remote found".to_string())
-    })?;
+
+    // The PR comes from wherever this branch is pushed to (the fork, in a
+    // fork workflow), under the name it has there
+    let head = repo.head()?;
+    let local_branch = head.shorthand().unwrap_or("main");
+    let target = super::tracking::push_target(&repo, local_branch)?;
+    let branch = target.dst.strip_prefix("refs/heads/").unwrap_or(&target.dst);
+
+    let remote = repo.find_remote(&target.remote)?;
     let url = remote.url().ok_or_else(|| {
-        AppError::General("Origin remote has no URL".to_string())
+        AppError::General(format!("Remote '{}' has no URL", target.remote))
     })?;
 
     // Normalize SSH/HTTPS URL to base HTTPS URL
     let base = normalize_remote_url(url)?;
 
-    // Get current branch name
-    let head = repo.head()?;
-    let branch = head.shorthand().unwrap_or("main");
-
     // Detect service and build PR creation URL
     let pr_url = if base.contains("github.com") {
         format!("{}/compare/{}?expand=1", base, branch)
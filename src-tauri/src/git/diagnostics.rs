@@ -0,0 +1,250 @@
+use git2::{ErrorClass, ErrorCode, Repository};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use crate::db::models::{CheckStatus, DiagnosticCheck, RepoDiagnostics};
+
+/// Lock files git leaves behind when a process dies mid-write.
+const LOCK_FILES: &[&str] = &[
+    "index.lock",
+    "HEAD.lock",
+    "ORIG_HEAD.lock",
+    "config.lock",
+    "packed-refs.lock",
+    "shallow.lock",
+];
+
+/// Turn a `Repository::open` failure into a sentence that says what is
+/// actually wrong, rather than libgit2's generic wording.
+pub fn describe_open_error(path: &Path, e: &git2::Error) -> String {
+    if let Some(gitdir) = broken_gitfile(path) {
+        return format!(
+            "Worktree link is broken: {} does not exist (was the main repository moved?)",
+            gitdir.display()
+        );
+    }
+
+    let message = e.message();
+    let lower = message.to_lowercase();
+    if lower.contains("permission denied") {
+        format!("Permission denied: {}", message)
+    } else if lower.contains("unsupported extension") {
+        format!("Repository uses an unsupported format extension: {}", message)
+    } else if e.code() == ErrorCode::NotFound {
+        format!("Not a git repository: {}", message)
+    } else if e.class() == ErrorClass::Config {
+        format!("Invalid repository config: {}", message)
+    } else {
+        format!("Cannot open repository: {}", message)
+    }
+}
+
+/// If `<path>/.git` is a gitfile (as in linked worktrees and submodules)
+/// whose target is missing, return that target.
+fn broken_gitfile(path: &Path) -> Option<PathBuf> {
+    let dot_git = path.join(".git");
+    if !dot_git.is_file() {
+        return None;
+    }
+    let content = fs::read_to_string(&dot_git).ok()?;
+    let target = content.trim().strip_prefix("gitdir:")?.trim();
+    let target = path.join(target);
+    (!target.exists()).then_some(target)
+}
+
+/// Run deeper checks than a status refresh does: whether the repo opens,
+/// HEAD resolves, the object database and index are readable, and whether
+/// stale lock files are blocking git.
+pub fn diagnose(path: &Path) -> RepoDiagnostics {
+    let mut checks = Vec::new();
+
+    match Repository::open(path) {
+        Ok(repo) => {
+            checks.push(check("Open repository", CheckStatus::Ok, "Opened successfully"));
+            checks.push(check_head(&repo));
+            checks.push(check_objects(&repo));
+            checks.push(check_index(&repo));
+            checks.push(check_locks(&repo));
+        }
+        Err(e) => {
+            checks.push(check(
+                "Open repository",
+                CheckStatus::Failed,
+                describe_open_error(path, &e),
+            ));
+        }
+    }
+
+    RepoDiagnostics {
+        path: path.to_string_lossy().to_string(),
+        healthy: checks.iter().all(|c| c.status != CheckStatus::Failed),
+        checks,
+    }
+}
+
+fn check(name: &str, status: CheckStatus, detail: impl Into<String>) -> DiagnosticCheck {
+    DiagnosticCheck {
+        name: name.to_string(),
+        status,
+        detail: detail.into(),
+    }
+}
+
+fn check_head(repo: &Repository) -> DiagnosticCheck {
+    const NAME: &str = "HEAD";
+
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == ErrorCode::UnbornBranch => {
+            return check(NAME, CheckStatus::Warning, "Branch has no commits yet");
+        }
+        Err(e) => {
+            return check(
+                NAME,
+                CheckStatus::Failed,
+                format!("HEAD cannot be resolved: {}", e.message()),
+            );
+        }
+    };
+
+    let Some(oid) = head.target() else {
+        return check(NAME, CheckStatus::Failed, "HEAD does not point at a commit");
+    };
+    if let Err(e) = repo.find_commit(oid) {
+        return check(
+            NAME,
+            CheckStatus::Failed,
+            format!("HEAD points at missing commit {}: {}", oid, e.message()),
+        );
+    }
+
+    let target = if head.is_branch() {
+        head.shorthand().unwrap_or("unknown").to_string()
+    } else {
+        format!("detached at {}", &oid.to_string()[..7])
+    };
+    check(NAME, CheckStatus::Ok, target)
+}
+
+fn check_objects(repo: &Repository) -> DiagnosticCheck {
+    const NAME: &str = "Object database";
+
+    let odb = match repo.odb() {
+        Ok(odb) => odb,
+        Err(e) => {
+            return check(
+                NAME,
+                CheckStatus::Failed,
+                format!("Cannot open object database: {}", e.message()),
+            );
+        }
+    };
+
+    let mut total = 0usize;
+    let mut unreadable = Vec::new();
+    let walked = odb.foreach(|oid| {
+        total += 1;
+        if odb.read_header(*oid).is_err() {
+            unreadable.push(*oid);
+        }
+        true
+    });
+
+    if let Err(e) = walked {
+        return check(
+            NAME,
+            CheckStatus::Failed,
+            format!("Cannot list objects: {}", e.message()),
+        );
+    }
+    match unreadable.first() {
+        None => check(NAME, CheckStatus::Ok, format!("{} objects readable", total)),
+        Some(first) => check(
+            NAME,
+            CheckStatus::Failed,
+            format!(
+                "{} of {} objects unreadable (first: {})",
+                unreadable.len(),
+                total,
+                first
+            ),
+        ),
+    }
+}
+
+fn check_index(repo: &Repository) -> DiagnosticCheck {
+    const NAME: &str = "Index";
+
+    if repo.is_bare() {
+        return check(NAME, CheckStatus::Ok, "Bare repository has no index");
+    }
+    match repo.index() {
+        Ok(index) if index.has_conflicts() => {
+            check(NAME, CheckStatus::Warning, "Index has unresolved conflicts")
+        }
+        Ok(index) => check(NAME, CheckStatus::Ok, format!("{} entries", index.len())),
+        Err(e) => check(
+            NAME,
+            CheckStatus::Failed,
+            format!("Index is unreadable: {}", e.message()),
+        ),
+    }
+}
+
+fn check_locks(repo: &Repository) -> DiagnosticCheck {
+    const NAME: &str = "Lock files";
+
+    // Linked worktrees keep their own HEAD/index but share refs and config
+    let mut dirs = vec![repo.path().to_path_buf()];
+    if repo.commondir() != repo.path() {
+        dirs.push(repo.commondir().to_path_buf());
+    }
+
+    let mut found = Vec::new();
+    for dir in &dirs {
+        for name in LOCK_FILES {
+            let lock = dir.join(name);
+            if lock.is_file() {
+                found.push(lock);
+            }
+        }
+        let refs = WalkDir::new(dir.join("refs"))
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "lock"))
+            .map(|e| e.into_path());
+        found.extend(refs);
+    }
+
+    if found.is_empty() {
+        return check(NAME, CheckStatus::Ok, "No lock files");
+    }
+    let listed: Vec<String> = found
+        .iter()
+        .map(|lock| match lock_age(lock) {
+            Some(age) => format!("{} ({} old)", lock.display(), age),
+            None => lock.display().to_string(),
+        })
+        .collect();
+    check(
+        NAME,
+        CheckStatus::Warning,
+        format!(
+            "Another git process may be running, or these are stale: {}",
+            listed.join(", ")
+        ),
+    )
+}
+
+fn lock_age(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let secs = SystemTime::now().duration_since(modified).ok()?.as_secs();
+    Some(match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    })
+}
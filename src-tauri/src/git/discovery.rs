@@ -1,32 +1,347 @@
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
-use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-/// Discover all Git repositories under the given root directory.
-/// Uses the `ignore` crate to respect .gitignore rules and skip
-/// hidden/ignored directories efficiently.
-pub fn discover_repos(root: &Path) -> Vec<PathBuf> {
-    let mut repos = Vec::new();
+use crate::db::models::RepoKind;
+use crate::error::AppError;
 
-    if !root.exists() || !root.is_dir() {
-        return repos;
+/// Directories skipped under a new scan root: dependency and build output
+/// trees that are large and rarely hold repos of their own.
+const DEFAULT_EXCLUDES: &[&str] = &["node_modules", "target", "vendor"];
+
+/// What discovery does inside a repo's working tree once it has found the repo.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NestedRepos {
+    /// Stop at the repo; nothing inside its working tree is walked.
+    #[default]
+    Skip,
+    /// Keep walking for repos nested inside, but not into directories the
+    /// enclosing repo ignores.
+    Nested,
+    /// Also walk ignored directories, where vendored checkouts usually live.
+    Vendored,
+}
+
+/// A directory to scan for repos, with its own discovery settings.
+///
+/// Globs are matched against paths relative to the root. A glob without a
+/// `/` matches a directory name at any depth (`node_modules`); one with a
+/// `/` matches the whole relative path (`work/*/archive`). In config files a
+/// bare string is accepted as a root with default settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "ScanRootEntry")]
+pub struct ScanRoot {
+    pub path: String,
+    /// How many directories deep to look below the root.
+    pub max_depth: Option<usize>,
+    /// When non-empty, only repos whose path matches one of these are reported.
+    pub include: Vec<String>,
+    /// Directories not descended into.
+    pub exclude: Vec<String>,
+    pub follow_symlinks: bool,
+    pub enabled: bool,
+    /// Whether to look for repos inside other repos.
+    pub nested: NestedRepos,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScanRootEntry {
+    Path(String),
+    Root {
+        path: String,
+        #[serde(default = "default_max_depth")]
+        max_depth: Option<usize>,
+        #[serde(default)]
+        include: Vec<String>,
+        #[serde(default = "default_excludes")]
+        exclude: Vec<String>,
+        #[serde(default)]
+        follow_symlinks: bool,
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        #[serde(default)]
+        nested: NestedRepos,
+    },
+}
+
+impl From<ScanRootEntry> for ScanRoot {
+    fn from(entry: ScanRootEntry) -> Self {
+        match entry {
+            ScanRootEntry::Path(path) => ScanRoot::new(path),
+            ScanRootEntry::Root {
+                path,
+                max_depth,
+                include,
+                exclude,
+                follow_symlinks,
+                enabled,
+                nested,
+            } => ScanRoot {
+                path,
+                max_depth,
+                include,
+                exclude,
+                follow_symlinks,
+                enabled,
+                nested,
+            },
+        }
+    }
+}
+
+fn default_max_depth() -> Option<usize> {
+    Some(5)
+}
+
+fn default_excludes() -> Vec<String> {
+    DEFAULT_EXCLUDES.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ScanRoot {
+    /// A root at `path` with default settings.
+    pub fn new(path: impl Into<String>) -> Self {
+        ScanRoot {
+            path: path.into(),
+            max_depth: default_max_depth(),
+            include: Vec::new(),
+            exclude: default_excludes(),
+            follow_symlinks: false,
+            enabled: default_enabled(),
+            nested: NestedRepos::default(),
+        }
+    }
+
+    /// Fail on the first glob that doesn't parse.
+    pub fn validate(&self) -> Result<(), AppError> {
+        for glob in self.include.iter().chain(&self.exclude) {
+            Glob::new(glob).map_err(|e| {
+                AppError::General(format!("Invalid glob '{}' in scan root {}: {}", glob, self.path, e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Globs split by what they match against: a directory's name, or its path
+/// relative to the root.
+struct Matcher {
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+impl Matcher {
+    /// Invalid globs are skipped; `ScanRoot::validate` reports them when roots are saved.
+    fn new(globs: &[String]) -> Self {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+        for glob in globs {
+            let Ok(compiled) = GlobBuilder::new(glob).literal_separator(true).build() else {
+                continue;
+            };
+            if glob.contains('/') {
+                paths.add(compiled);
+            } else {
+                names.add(compiled);
+            }
+        }
+        Matcher {
+            names: names.build().unwrap_or_else(|_| GlobSet::empty()),
+            paths: paths.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.paths.is_empty()
+    }
+
+    fn matches(&self, rel: &Path) -> bool {
+        rel.file_name().is_some_and(|name| self.names.is_match(name)) || self.paths.is_match(rel)
+    }
+}
+
+/// Where a repo keeps its git dir and how it relates to other repos.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub kind: RepoKind,
+    pub git_dir: PathBuf,
+    /// The main repo of a linked worktree, or the superproject of a submodule.
+    pub parent: Option<PathBuf>,
+}
+
+/// Work out whether `dir` is a repository, and of what kind, from its
+/// `.git` directory or file, or from a bare repo's own layout. Only stats
+/// and reads a few small files, so it is cheap enough to call for every
+/// directory walked.
+pub fn classify(dir: &Path) -> Option<Layout> {
+    let dot_git = dir.join(".git");
+    match fs::metadata(&dot_git) {
+        Ok(meta) if meta.is_dir() => Some(Layout {
+            kind: RepoKind::Standard,
+            git_dir: dot_git,
+            parent: None,
+        }),
+        Ok(meta) if meta.is_file() => classify_gitfile(dir, &dot_git),
+        _ => is_bare(dir).then(|| Layout {
+            kind: RepoKind::Bare,
+            git_dir: dir.to_path_buf(),
+            parent: None,
+        }),
+    }
+}
+
+/// A `.git` file (`gitdir: <path>`) is written for linked worktrees,
+/// submodules and `--separate-git-dir` repos; the target tells them apart.
+fn classify_gitfile(dir: &Path, dot_git: &Path) -> Option<Layout> {
+    let content = fs::read_to_string(dot_git).ok()?;
+    let target = content.trim().strip_prefix("gitdir:")?.trim();
+    let git_dir = normalize(&dir.join(target));
+
+    // Linked worktrees name the shared git dir in `commondir`
+    if let Ok(common) = fs::read_to_string(git_dir.join("commondir")) {
+        let common = normalize(&git_dir.join(common.trim()));
+        return Some(Layout {
+            kind: RepoKind::Worktree,
+            parent: Some(work_dir_of(&common)),
+            git_dir,
+        });
+    }
+    // Otherwise go by where the git dir sits: `<main>/.git/worktrees/<name>`
+    // (a worktree whose link is broken) or `<superproject>/.git/modules/<name>`
+    let (kind, parent) = if let Some(main) = owner(&git_dir, "worktrees") {
+        (RepoKind::Worktree, Some(main))
+    } else if let Some(superproject) = owner(&git_dir, "modules") {
+        (RepoKind::Submodule, Some(superproject))
+    } else {
+        (RepoKind::SeparateGitDir, None)
+    };
+    Some(Layout {
+        kind,
+        git_dir,
+        parent,
+    })
+}
+
+/// A bare repo is a directory with git's own layout that isn't a `.git` dir.
+fn is_bare(dir: &Path) -> bool {
+    dir.file_name().is_some_and(|n| n != ".git")
+        && dir.join("HEAD").is_file()
+        && dir.join("objects").is_dir()
+        && dir.join("refs").is_dir()
+}
+
+/// The working tree a git dir belongs to: the parent of a `.git` dir, or
+/// the git dir itself for a bare repo.
+fn work_dir_of(git_dir: &Path) -> PathBuf {
+    match git_dir.parent() {
+        Some(parent) if git_dir.file_name().is_some_and(|n| n == ".git") => parent.to_path_buf(),
+        _ => git_dir.to_path_buf(),
+    }
+}
+
+/// For a path like `<repo>/.git/<area>/...`, the `<repo>` it belongs to.
+fn owner(git_dir: &Path, area: &str) -> Option<PathBuf> {
+    let parts: Vec<Component> = git_dir.components().collect();
+    let at = parts
+        .windows(2)
+        .position(|w| w[0].as_os_str() == ".git" && w[1].as_os_str() == area)?;
+    Some(parts[..at].iter().collect())
+}
+
+/// The nearest repo whose working tree contains `path`, not counting `path`
+/// itself. For submodules this is the superproject.
+pub fn enclosing_repo(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| classify(dir).is_some_and(|layout| layout.kind != RepoKind::Bare))
+        .map(Path::to_path_buf)
+}
+
+/// Resolve `.` and `..` without touching the filesystem, so targets that
+/// no longer exist can still be classified.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for part in path.components() {
+        match part {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
     }
+    out
+}
+
+/// Walk `root` for Git repositories, calling `on_repo` with each one found
+/// and `on_dir` for every directory visited. Uses the `ignore` crate to walk
+/// efficiently; returning false from `on_dir` stops the walk. Git dirs and
+/// the insides of bare repos are never walked; other repos' working trees
+/// are only walked as the root's `nested` setting allows.
+pub fn walk_repos(root: &ScanRoot, mut on_dir: impl FnMut() -> bool, mut on_repo: impl FnMut(PathBuf)) {
+    let base = PathBuf::from(&root.path);
+    if !root.enabled || !base.is_dir() {
+        return;
+    }
+
+    let include = Matcher::new(&root.include);
+    let exclude = Matcher::new(&root.exclude);
+    let prefix = base.clone();
+    // Directories whose contents are skipped. The walk is sequential, so a
+    // directory is added here before its children reach `filter_entry`.
+    let pruned: Arc<Mutex<HashSet<PathBuf>>> = Arc::default();
+    let skip = pruned.clone();
 
-    let walker = WalkBuilder::new(root)
+    // Inside a repo, `Nested` leaves out what the repo ignores (build output,
+    // dependencies); the ignore crate applies .gitignore only within repos
+    let respect_ignores = root.nested == NestedRepos::Nested;
+
+    let walker = WalkBuilder::new(&base)
         .hidden(false) // Don't skip hidden directories (repos can be in hidden dirs)
-        .git_ignore(false) // Don't use .gitignore for discovery
+        .git_ignore(respect_ignores)
         .git_global(false)
-        .git_exclude(false)
-        .max_depth(Some(5)) // Don't recurse too deep
+        .git_exclude(respect_ignores)
+        .max_depth(root.max_depth)
+        .follow_links(root.follow_symlinks)
+        .filter_entry(move |entry| {
+            let path = entry.path();
+            if path.file_name().is_some_and(|n| n == ".git") {
+                return false;
+            }
+            if path.parent().is_some_and(|p| skip.lock().unwrap().contains(p)) {
+                return false;
+            }
+            let rel = path.strip_prefix(&prefix).unwrap_or(path);
+            rel.as_os_str().is_empty() || !exclude.matches(rel)
+        })
         .build();
 
     for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_dir()) {
+            continue;
+        }
+        if !on_dir() {
+            return;
+        }
         let path = entry.path();
-        if path.file_name().map_or(false, |n| n == ".git") && path.is_dir() {
-            if let Some(parent) = path.parent() {
-                repos.push(parent.to_path_buf());
-            }
+        let Some(layout) = classify(path) else {
+            continue;
+        };
+        if layout.kind == RepoKind::Bare || root.nested == NestedRepos::Skip {
+            pruned.lock().unwrap().insert(path.to_path_buf());
+        }
+        let rel = path.strip_prefix(&base).unwrap_or(path);
+        if include.is_empty() || include.matches(rel) {
+            on_repo(path.to_path_buf());
         }
     }
-
-    repos
 }
@@ -0,0 +1,69 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+use crate::git::discovery;
+
+/// Files in the git dir whose change means the repo's status may have
+/// changed: what HEAD points at, the index, and fetch results.
+const GITDIR_FILES: &[&str] = &["HEAD", "index", "FETCH_HEAD"];
+
+/// Files shared between linked worktrees: packed refs and the config that
+/// holds remotes and upstreams.
+const COMMONDIR_FILES: &[&str] = &["packed-refs", "config"];
+
+/// A cheap fingerprint of a repo's git metadata, built from the size and
+/// modification time of HEAD, the index, FETCH_HEAD, packed-refs, config and
+/// every loose ref. If it matches the stored one, the repo's status is
+/// assumed unchanged and not recomputed.
+///
+/// Edits to the working tree that haven't been staged don't touch any of
+/// these, so a changed file count is only picked up by a full rescan or a
+/// per-repo refresh.
+pub fn compute(path: &Path) -> Option<String> {
+    let gitdir = discovery::classify(path)?.git_dir;
+    let commondir = fs::read_to_string(gitdir.join("commondir"))
+        .map(|c| gitdir.join(c.trim()))
+        .unwrap_or_else(|_| gitdir.clone());
+
+    let mut hasher = DefaultHasher::new();
+    for name in GITDIR_FILES {
+        hash_file(&mut hasher, &gitdir.join(name));
+    }
+    for name in COMMONDIR_FILES {
+        hash_file(&mut hasher, &commondir.join(name));
+    }
+
+    // Loose refs, including remote-tracking branches and the stash
+    let mut refs: Vec<PathBuf> = WalkDir::new(commondir.join("refs"))
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+    refs.sort();
+    for path in &refs {
+        hash_file(&mut hasher, path);
+    }
+
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+fn hash_file(hasher: &mut DefaultHasher, path: &Path) {
+    path.hash(hasher);
+    match fs::metadata(path) {
+        Ok(meta) => {
+            meta.len().hash(hasher);
+            meta.modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .hash(hasher);
+        }
+        // Appearing and disappearing (e.g. FETCH_HEAD, the index) counts as a change
+        Err(_) => 0u8.hash(hasher),
+    }
+}
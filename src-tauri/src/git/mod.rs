@@ -1,4 +1,13 @@
+pub mod clone;
 pub mod detail;
+pub mod diagnostics;
 pub mod discovery;
+pub mod fingerprint;
 pub mod operations;
+pub mod progress;
+pub mod state;
 pub mod status;
+pub mod submodules;
+pub mod tracking;
+pub mod unpushed;
+pub mod worktrees;
@@ -4,9 +4,16 @@ use git2::{
 };
 use std::path::Path;
 use std::sync::Once;
+use std::time::{Duration, Instant};
 
+use super::progress::{NetworkProgress, OpContext};
+use super::{state, tracking};
 use crate::error::AppError;
 
+/// Minimum gap between transfer progress reports, so large fetches don't
+/// flood the frontend with thousands of events per second.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Ensure SSH_AUTH_SOCK is set. On macOS, GUI apps don't inherit shell
 /// environment variables, so the SSH agent socket must be discovered.
 fn ensure_ssh_auth_sock() {
@@ -31,12 +38,84 @@ fn ensure_ssh_auth_sock() {
     });
 }
 
-/// Build remote callbacks with SSH agent, SSH key, and HTTPS credential support.
-fn make_callbacks() -> RemoteCallbacks<'static> {
+/// Build remote callbacks with SSH agent, SSH key, and HTTPS credential support,
+/// reporting transfer progress for `remote` in the repo at `path` through `ctx`.
+/// Returning `false` from a progress callback makes libgit2 abort the transfer,
+/// which is how cancellation takes effect mid-operation.
+fn make_callbacks(path: &Path, remote: &str, ctx: &OpContext) -> RemoteCallbacks<'static> {
     ensure_ssh_auth_sock();
 
     let mut callbacks = RemoteCallbacks::new();
 
+    let (progress_ctx, progress_path, progress_remote) =
+        (ctx.clone(), path.to_path_buf(), remote.to_string());
+    let mut last_report: Option<Instant> = None;
+    callbacks.transfer_progress(move |stats| {
+        let done = stats.received_objects() == stats.total_objects()
+            && stats.indexed_deltas() == stats.total_deltas();
+        if done || last_report.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+            last_report = Some(Instant::now());
+            progress_ctx.report(
+                &progress_path,
+                NetworkProgress::Transfer {
+                    remote: progress_remote.clone(),
+                    received_objects: stats.received_objects(),
+                    indexed_objects: stats.indexed_objects(),
+                    local_objects: stats.local_objects(),
+                    total_objects: stats.total_objects(),
+                    indexed_deltas: stats.indexed_deltas(),
+                    total_deltas: stats.total_deltas(),
+                    received_bytes: stats.received_bytes(),
+                },
+            );
+        }
+        !progress_ctx.cancel.is_cancelled()
+    });
+
+    let (sideband_ctx, sideband_path, sideband_remote) =
+        (ctx.clone(), path.to_path_buf(), remote.to_string());
+    callbacks.sideband_progress(move |data| {
+        let message = String::from_utf8_lossy(data).trim().to_string();
+        if !message.is_empty() {
+            sideband_ctx.report(
+                &sideband_path,
+                NetworkProgress::Remote {
+                    remote: sideband_remote.clone(),
+                    message,
+                },
+            );
+        }
+        !sideband_ctx.cancel.is_cancelled()
+    });
+
+    let (push_ctx, push_path, push_remote) = (ctx.clone(), path.to_path_buf(), remote.to_string());
+    let mut last_push_report: Option<Instant> = None;
+    callbacks.push_transfer_progress(move |current, total, bytes| {
+        if current == total || last_push_report.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+            last_push_report = Some(Instant::now());
+            push_ctx.report(
+                &push_path,
+                NetworkProgress::Push {
+                    remote: push_remote.clone(),
+                    current,
+                    total,
+                    bytes,
+                },
+            );
+        }
+    });
+
+    // Push transfer progress can't abort, so give cancellation one last chance
+    // after negotiation, before any objects are sent.
+    let negotiation_cancel = ctx.cancel.clone();
+    callbacks.push_negotiation(move |_| {
+        if negotiation_cancel.is_cancelled() {
+            Err(git2::Error::from_str("cancelled"))
+        } else {
+            Ok(())
+        }
+    });
+
     callbacks.credentials(|url, username_from_url, allowed_types| {
         // Try SSH agent first
         if allowed_types.contains(CredentialType::SSH_KEY) {
@@ -102,24 +181,38 @@ fn make_callbacks() -> RemoteCallbacks<'static> {
     callbacks
 }
 
-fn make_fetch_options() -> FetchOptions<'static> {
+pub(super) fn make_fetch_options(path: &Path, remote: &str, ctx: &OpContext) -> FetchOptions<'static> {
     let mut fetch_opts = FetchOptions::new();
-    fetch_opts.remote_callbacks(make_callbacks());
+    fetch_opts.remote_callbacks(make_callbacks(path, remote, ctx));
     fetch_opts.download_tags(AutotagOption::All);
     fetch_opts
 }
 
-fn make_push_options() -> PushOptions<'static> {
+fn make_push_options(path: &Path, remote: &str, ctx: &OpContext) -> PushOptions<'static> {
     let mut push_opts = PushOptions::new();
-    push_opts.remote_callbacks(make_callbacks());
+    push_opts.remote_callbacks(make_callbacks(path, remote, ctx));
     push_opts
 }
 
+/// Map a libgit2 error to `AppError::Cancelled` if it was caused by `ctx`
+/// being cancelled (progress callbacks abort the transfer with a user error).
+pub(super) fn cancelled_or(ctx: &OpContext, e: git2::Error) -> AppError {
+    if ctx.cancel.is_cancelled() {
+        AppError::Cancelled
+    } else {
+        AppError::Git(e)
+    }
+}
+
 /// Fetch all remotes for the repository at the given path.
-pub fn fetch_repo(path: &Path) -> Result<(), AppError> {
+pub fn fetch_repo(path: &Path, ctx: &OpContext) -> Result<(), AppError> {
+    ctx.cancel.check()?;
     let repo = Repository::open(path)?;
 
     let remotes = repo.remotes()?;
+    if remotes.is_empty() {
+        return Err(AppError::NoRemotes);
+    }
     for remote_name in remotes.iter().flatten() {
         let mut remote = repo.find_remote(remote_name)?;
 
@@ -131,28 +224,40 @@ pub fn fetch_repo(path: &Path) -> Result<(), AppError> {
             .collect();
         let refspec_refs: Vec<&str> = refspecs.iter().map(|s| s.as_str()).collect();
 
-        remote.fetch(&refspec_refs, Some(&mut make_fetch_options()), None)?;
+        remote
+            .fetch(
+                &refspec_refs,
+                Some(&mut make_fetch_options(path, remote_name, ctx)),
+                None,
+            )
+            .map_err(|e| cancelled_or(ctx, e))?;
     }
 
     Ok(())
 }
 
 /// Fetch then rebase the current branch onto its upstream.
-pub fn pull_rebase_repo(path: &Path) -> Result<(), AppError> {
-    fetch_repo(path)?;
+pub fn pull_rebase_repo(path: &Path, ctx: &OpContext) -> Result<(), AppError> {
+    fetch_repo(path, ctx)?;
+    ctx.cancel.check()?;
 
     let repo = Repository::open(path)?;
+    state::ensure_clean_state(&repo)?;
 
     let head = repo.head()?;
+    if !head.is_branch() {
+        return Err(AppError::DetachedHead);
+    }
     let branch_name = head
         .shorthand()
-        .ok_or_else(|| AppError::General("HEAD is not on a branch".to_string()))?
+        .ok_or(AppError::DetachedHead)?
         .to_string();
 
-    let upstream_ref_name = format!("refs/remotes/origin/{}", branch_name);
-    let upstream_ref = repo.find_reference(&upstream_ref_name).map_err(|_| {
-        AppError::General(format!("No upstream branch found for {}", branch_name))
-    })?;
+    let upstream_ref_name = tracking::upstream_ref(&repo, &branch_name)
+        .ok_or_else(|| AppError::NoUpstream(branch_name.clone()))?;
+    let upstream_ref = repo
+        .find_reference(&upstream_ref_name)
+        .map_err(|_| AppError::NoUpstream(branch_name.clone()))?;
     let upstream_commit = repo.reference_to_annotated_commit(&upstream_ref)?;
 
     rebase_onto(&repo, &upstream_commit)?;
@@ -168,7 +273,7 @@ fn rebase_onto(repo: &Repository, upstream: &AnnotatedCommit) -> Result<(), AppE
         let index = repo.index()?;
         if index.has_conflicts() {
             rebase.abort()?;
-            return Err(AppError::General(
+            return Err(AppError::Conflict(
                 "Rebase aborted: conflicts detected".to_string(),
             ));
         }
@@ -181,22 +286,34 @@ fn rebase_onto(repo: &Repository, upstream: &AnnotatedCommit) -> Result<(), AppE
     Ok(())
 }
 
-/// Push the current branch to origin.
-pub fn push_repo(path: &Path) -> Result<(), AppError> {
+/// Push the current branch to its push remote, as resolved from git config.
+pub fn push_repo(path: &Path, ctx: &OpContext) -> Result<(), AppError> {
+    ctx.cancel.check()?;
     let repo = Repository::open(path)?;
 
     let head = repo.head()?;
+    if !head.is_branch() {
+        return Err(AppError::DetachedHead);
+    }
     let branch_name = head
         .shorthand()
-        .ok_or_else(|| AppError::General("HEAD is not on a branch".to_string()))?
+        .ok_or(AppError::DetachedHead)?
         .to_string();
 
-    let mut remote = repo
-        .find_remote("origin")
-        .map_err(|_| AppError::General("No 'origin' remote found".to_string()))?;
+    let target = tracking::push_target(&repo, &branch_name)?;
+    let mut remote = repo.find_remote(&target.remote)?;
 
-    let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
-    remote.push(&[&refspec], Some(&mut make_push_options()))?;
+    let refspec = format!("refs/heads/{}:{}", branch_name, target.dst);
+    remote
+        .push(
+            &[&refspec],
+            Some(&mut make_push_options(path, &target.remote, ctx)),
+        )
+        .map_err(|e| cancelled_or(ctx, e))?;
+
+    if target.set_upstream {
+        tracking::set_upstream(&repo, &branch_name, &target.remote, &target.dst)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,73 @@
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::AppError;
+
+/// Shared flag used to stop a network operation (or a whole bulk run) partway through.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Return `Err(AppError::Cancelled)` once the token has been cancelled.
+    pub fn check(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            Err(AppError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A progress update from a fetch or push, reported per repo.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum NetworkProgress {
+    /// Objects being downloaded and indexed during a fetch.
+    Transfer {
+        remote: String,
+        received_objects: usize,
+        indexed_objects: usize,
+        local_objects: usize,
+        total_objects: usize,
+        indexed_deltas: usize,
+        total_deltas: usize,
+        received_bytes: usize,
+    },
+    /// Objects being uploaded during a push.
+    Push {
+        remote: String,
+        current: usize,
+        total: usize,
+        bytes: usize,
+    },
+    /// Text the server sent over the sideband ("Counting objects: ...").
+    Remote { remote: String, message: String },
+}
+
+pub type ProgressFn = Arc<dyn Fn(&Path, NetworkProgress) + Send + Sync>;
+
+/// Progress sink and cancellation token threaded through network operations.
+/// The default context reports nothing and is never cancelled.
+#[derive(Clone, Default)]
+pub struct OpContext {
+    pub progress: Option<ProgressFn>,
+    pub cancel: CancelToken,
+}
+
+impl OpContext {
+    pub fn report(&self, path: &Path, progress: NetworkProgress) {
+        if let Some(sink) = &self.progress {
+            sink(path, progress);
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use git2::build::CheckoutBuilder;
+use git2::{ErrorCode, Index, Oid, Rebase, Repository, RepositoryState, ResetType};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::db::models::InProgressOperation;
+use crate::error::AppError;
+
+/// The multi-step operation the repo was left in the middle of, if any.
+pub fn in_progress(repo: &Repository) -> Option<InProgressOperation> {
+    match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some(InProgressOperation::Merge),
+        RepositoryState::Revert | RepositoryState::RevertSequence => {
+            Some(InProgressOperation::Revert)
+        }
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            Some(InProgressOperation::CherryPick)
+        }
+        RepositoryState::Bisect => Some(InProgressOperation::Bisect),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => Some(InProgressOperation::Rebase),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+            Some(InProgressOperation::ApplyMailbox)
+        }
+    }
+}
+
+/// Fail with `InProgress` if the repo is mid-merge, mid-rebase, etc.
+pub fn ensure_clean_state(repo: &Repository) -> Result<(), AppError> {
+    match in_progress(repo) {
+        Some(op) => Err(AppError::InProgress(op.label().to_string())),
+        None => Ok(()),
+    }
+}
+
+// ── Continue ────────────────────────────────────────────
+
+/// Finish the in-progress operation once its conflicts have been resolved
+/// and staged: commit the merge, cherry-pick or revert, or carry on rebasing.
+/// Rebases libgit2 didn't start, multi-commit sequences and `git am` are
+/// handed to the git CLI.
+pub fn continue_operation(path: &Path) -> Result<(), AppError> {
+    let mut repo = Repository::open(path)?;
+    match repo.state() {
+        RepositoryState::Merge => commit_merge(&mut repo),
+        RepositoryState::CherryPick => commit_pick(&repo, "CHERRY_PICK_HEAD"),
+        RepositoryState::Revert => commit_pick(&repo, "REVERT_HEAD"),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => match repo.open_rebase(None) {
+            Ok(mut rebase) => {
+                resolved_index(&repo)?;
+                if rebase.operation_current().is_some() {
+                    commit_rebase_step(&repo, &mut rebase)?;
+                }
+                run_rebase(&repo, &mut rebase)
+            }
+            Err(_) => run_git(&repo, &["rebase", "--continue"]),
+        },
+        state => match sequencer_command(state) {
+            Some(command) => run_git(&repo, &[command, "--continue"]),
+            None => Err(unsupported(state, "continue")),
+        },
+    }
+}
+
+fn commit_merge(repo: &mut Repository) -> Result<(), AppError> {
+    let mut merge_heads = Vec::new();
+    repo.mergehead_foreach(|oid| {
+        merge_heads.push(*oid);
+        true
+    })?;
+
+    let mut index = resolved_index(repo)?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let mut parents = vec![head];
+    for oid in merge_heads {
+        parents.push(repo.find_commit(oid)?);
+    }
+    let parent_refs: Vec<_> = parents.iter().collect();
+
+    let sig = repo.signature()?;
+    let message = pending_message(repo).unwrap_or_else(|| "Merge commit".to_string());
+    repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &parent_refs)?;
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+/// Commit a stopped cherry-pick or revert. Cherry-picks keep the original
+/// author; both reuse the message git prepared in MERGE_MSG.
+fn commit_pick(repo: &Repository, pick_head: &str) -> Result<(), AppError> {
+    let mut index = resolved_index(repo)?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let picked = repo.find_reference(pick_head)?.peel_to_commit()?;
+
+    let committer = repo.signature()?;
+    let author = if pick_head == "CHERRY_PICK_HEAD" {
+        picked.author().to_owned()
+    } else {
+        committer.clone()
+    };
+    let message = pending_message(repo)
+        .or_else(|| picked.message().map(String::from))
+        .unwrap_or_default();
+
+    repo.commit(Some("HEAD"), &author, &committer, &message, &tree, &[&head])?;
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+/// MERGE_MSG with git's `#` comment lines (e.g. the conflict list) removed.
+fn pending_message(repo: &Repository) -> Option<String> {
+    let raw = repo.message().ok()?;
+    git2::message_prettify(raw, Some(b'#'))
+        .ok()
+        .filter(|m| !m.trim().is_empty())
+}
+
+fn resolved_index(repo: &Repository) -> Result<Index, AppError> {
+    let index = repo.index()?;
+    if index.has_conflicts() {
+        return Err(AppError::Conflict(
+            "Resolve and stage all conflicted files first".to_string(),
+        ));
+    }
+    Ok(index)
+}
+
+// ── Rebase ──────────────────────────────────────────────
+
+fn commit_rebase_step(repo: &Repository, rebase: &mut Rebase) -> Result<(), AppError> {
+    match rebase.commit(None, &repo.signature()?, None) {
+        Ok(_) => Ok(()),
+        // The change is already upstream; nothing to commit for this step
+        Err(e) if e.code() == ErrorCode::Applied => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Apply the remaining rebase steps, stopping (and leaving the rebase in
+/// progress) at the next conflict.
+fn run_rebase(repo: &Repository, rebase: &mut Rebase) -> Result<(), AppError> {
+    while let Some(op) = rebase.next() {
+        let op = op?;
+        if repo.index()?.has_conflicts() {
+            return Err(AppError::Conflict(format!(
+                "Rebase stopped: conflicts applying {}",
+                &op.id().to_string()[..7]
+            )));
+        }
+        commit_rebase_step(repo, rebase)?;
+    }
+    rebase.finish(None)?;
+    Ok(())
+}
+
+// ── Skip ────────────────────────────────────────────────
+
+/// Drop the change currently being applied: skip the stopped rebase step,
+/// sequence pick or `git am` patch and carry on, drop a stopped cherry-pick
+/// or revert, or skip the commit bisect is testing.
+pub fn skip_operation(path: &Path) -> Result<(), AppError> {
+    let repo = Repository::open(path)?;
+    match repo.state() {
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => match repo.open_rebase(None) {
+            Ok(mut rebase) => {
+                reset_hard(&repo, repo.head()?.peel_to_commit()?.id())?;
+                run_rebase(&repo, &mut rebase)
+            }
+            Err(_) => run_git(&repo, &["rebase", "--skip"]),
+        },
+        // git's `--skip` resets with `--merge`, keeping unrelated local changes
+        RepositoryState::CherryPick => run_git(&repo, &["cherry-pick", "--skip"]),
+        RepositoryState::Revert => run_git(&repo, &["revert", "--skip"]),
+        RepositoryState::Bisect => run_git(&repo, &["bisect", "skip"]),
+        state => match sequencer_command(state) {
+            Some(command) => run_git(&repo, &[command, "--skip"]),
+            None => Err(unsupported(state, "skip")),
+        },
+    }
+}
+
+// ── Abort ───────────────────────────────────────────────
+
+/// Abandon the in-progress operation and put the repo back the way it was
+/// before it started. Everything but bisect and rebases libgit2 started goes
+/// through git's own `--abort`, which keeps local changes that weren't part
+/// of a merge, cherry-pick or revert and restores a rebase's autostash.
+pub fn abort_operation(path: &Path) -> Result<(), AppError> {
+    let repo = Repository::open(path)?;
+    match repo.state() {
+        RepositoryState::Clean => Err(unsupported(RepositoryState::Clean, "abort")),
+        RepositoryState::Merge => run_git(&repo, &["merge", "--abort"]),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            run_git(&repo, &["cherry-pick", "--abort"])
+        }
+        RepositoryState::Revert | RepositoryState::RevertSequence => run_git(&repo, &["revert", "--abort"]),
+        RepositoryState::Bisect => abort_bisect(&repo),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+            run_git(&repo, &["am", "--abort"])
+        }
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => match repo.open_rebase(None) {
+            Ok(mut rebase) => Ok(rebase.abort()?),
+            Err(_) => run_git(&repo, &["rebase", "--abort"]),
+        },
+    }
+}
+
+/// Equivalent of `git bisect reset`: return to the commit or branch bisect
+/// started from and remove the bisect refs and state files.
+fn abort_bisect(repo: &Repository) -> Result<(), AppError> {
+    let start = fs::read_to_string(repo.path().join("BISECT_START")).unwrap_or_default();
+    let start = start.trim();
+
+    let branch_ref = format!("refs/heads/{}", start);
+    if !start.is_empty() && repo.find_reference(&branch_ref).is_ok() {
+        repo.set_head(&branch_ref)?;
+    } else if let Ok(oid) = Oid::from_str(start) {
+        repo.set_head_detached(oid)?;
+    }
+    repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+
+    let bisect_refs: Vec<String> = repo
+        .references_glob("refs/bisect/*")?
+        .flatten()
+        .filter_map(|r| r.name().map(String::from))
+        .collect();
+    for name in bisect_refs {
+        repo.find_reference(&name)?.delete()?;
+    }
+
+    for entry in fs::read_dir(repo.path())?.flatten() {
+        if entry.file_name().to_string_lossy().starts_with("BISECT_") {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+fn reset_hard(repo: &Repository, oid: Oid) -> Result<(), AppError> {
+    let target = repo.find_object(oid, None)?;
+    repo.reset(&target, ResetType::Hard, None)?;
+    Ok(())
+}
+
+/// The git command that drives a multi-step state libgit2 can't resume.
+fn sequencer_command(state: RepositoryState) -> Option<&'static str> {
+    match state {
+        RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        RepositoryState::RevertSequence => Some("revert"),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("am"),
+        _ => None,
+    }
+}
+
+/// Run `git <args>` in the repo. Editors are disabled so a continue keeps
+/// the message git prepared. A failure that leaves conflicts behind is a
+/// `Conflict`; a missing `git` is `Unsupported`, naming the command to run.
+fn run_git(repo: &Repository, args: &[&str]) -> Result<(), AppError> {
+    let command = format!("git {}", args.join(" "));
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo.workdir().unwrap_or(repo.path()))
+        .env("GIT_EDITOR", "true")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => AppError::Unsupported(format!(
+                "git isn't installed; run `{}` in a terminal",
+                command
+            )),
+            _ => e.into(),
+        })?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let detail = [stderr.trim(), stdout.trim()]
+        .into_iter()
+        .find(|s| !s.is_empty())
+        .unwrap_or("no output");
+    let message = format!("`{}` failed: {}", command, detail);
+    // Re-open to see the index git left behind, not the one cached here
+    let conflicted = Repository::open(repo.path())
+        .and_then(|repo| repo.index())
+        .is_ok_and(|index| index.has_conflicts());
+    Err(if conflicted {
+        AppError::Conflict(message)
+    } else {
+        AppError::General(message)
+    })
+}
+
+fn unsupported(state: RepositoryState, action: &str) -> AppError {
+    let what = match state {
+        RepositoryState::Clean => "No operation is in progress".to_string(),
+        RepositoryState::Bisect => format!("Can't {} a bisect; mark commits with `git bisect`", action),
+        _ => format!("Can't {} this operation", action),
+    };
+    AppError::Unsupported(what)
+}
@@ -1,64 +1,209 @@
-use git2::Repository;
+use git2::{Repository, Status};
 use std::path::Path;
 
-use crate::db::models::{RepoHealth, RepoInfo};
+use crate::db::models::{RepoHealth, RepoInfo, RepoKind};
+use crate::error::AppError;
+use crate::git::{diagnostics, discovery, fingerprint, state, submodules, tracking};
+use crate::health::{self, HealthRule};
 
-/// Get the full status of a Git repository.
-pub fn get_repo_info(path: &Path) -> RepoInfo {
+/// Ignored files at least this big are counted in `ignored_large_files`.
+/// The count is measured on demand by `count_ignored_large` rather than on
+/// every status read, and carried over by `keep_ignored_large`.
+const LARGE_IGNORED_BYTES: u64 = 10 * 1024 * 1024;
+
+/// How deep, and through how many entries per repo, ignored directories are
+/// walked looking for large files. A huge `node_modules` costs a bounded walk
+/// instead of a full one, at the price of missing files past the limits.
+const IGNORED_WALK_DEPTH: usize = 8;
+const IGNORED_WALK_ENTRIES: usize = 10_000;
+
+/// Get the full status of a Git repository, with `rules` applied to its health.
+pub fn get_repo_info(path: &Path, rules: &[HealthRule]) -> RepoInfo {
     let name = path
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
     let now = chrono::Utc::now().to_rfc3339();
+    // Taken before reading status, so a change made while reading is caught next scan
+    let fingerprint = fingerprint::compute(path);
+    let (kind, parent_path) = match discovery::classify(path) {
+        Some(layout) => (
+            layout.kind,
+            layout.parent.map(|p| p.to_string_lossy().to_string()),
+        ),
+        None => (RepoKind::Standard, None),
+    };
+    let nested_in = discovery::enclosing_repo(path).map(|p| p.to_string_lossy().to_string());
 
     let mut repo = match Repository::open(path) {
         Ok(r) => r,
-        Err(_) => {
+        Err(e) => {
             return RepoInfo {
-                id: None,
                 path: path.to_string_lossy().to_string(),
                 name,
                 branch: "unknown".to_string(),
-                ahead: 0,
-                behind: 0,
-                dirty_files: 0,
-                stash_count: 0,
                 health: RepoHealth::Error,
                 last_checked: now,
-                remote_url: None,
+                error_detail: Some(diagnostics::describe_open_error(path, &e)),
+                fingerprint,
+                kind,
+                parent_path,
+                nested_in,
+                ..RepoInfo::default()
             };
         }
     };
 
-    let branch = get_branch_name(&repo);
+    let (branch, detached) = get_head(&repo);
+    let upstream_ref = (!detached)
+        .then(|| tracking::upstream_ref(&repo, &branch))
+        .flatten();
+    let upstream_gone = upstream_ref
+        .as_deref()
+        .is_some_and(|name| repo.find_reference(name).is_err());
+    let no_upstream = !detached && upstream_ref.is_none();
+    let upstream = upstream_ref.as_deref().map(tracking::shorthand);
     let (ahead, behind) = get_ahead_behind(&repo);
-    let dirty_files = get_dirty_count(&repo);
+    let default_ref = tracking::default_branch(&repo, &branch);
+    let (ahead_default, behind_default) = default_ref
+        .as_deref()
+        .map(|name| ahead_behind_ref(&repo, name))
+        .unwrap_or((0, 0));
     let stash_count = get_stash_count(&mut repo);
     let remote_url = get_origin_url(&repo);
+    let no_origin = repo.find_remote("origin").is_err();
+    let remote_count = repo.remotes().map(|r| r.len() as u32).unwrap_or(0);
+    let (last_commit_at, last_commit_author) = get_last_commit(&repo);
+    let last_fetched_at = get_last_fetch_time(&repo);
+    let last_activity_at = get_last_activity_time(&repo);
+    let branch_since = get_branch_since(&repo, &branch);
+    let in_progress = state::in_progress(&repo);
+    let submodules = submodules::counts(&repo);
 
-    let health = determine_health(ahead, behind, dirty_files);
+    // A repo that opens but whose status can't be read (e.g. a corrupt index)
+    // is still an error, just a later one.
+    // A bare repo has no working tree to be dirty
+    let counts = if repo.is_bare() {
+        Ok(DirtyCounts::default())
+    } else {
+        get_dirty_counts(&repo)
+    };
+    let (dirty, error_detail) = match counts {
+        Ok(dirty) => (dirty, None),
+        Err(e) => (
+            DirtyCounts::default(),
+            Some(format!("Cannot read working tree status: {}", e.message())),
+        ),
+    };
 
-    RepoInfo {
+    let mut info = RepoInfo {
         id: None,
         path: path.to_string_lossy().to_string(),
         name,
         branch,
         ahead,
         behind,
-        dirty_files,
+        dirty_files: dirty.total,
         stash_count,
-        health,
+        health: RepoHealth::Clean,
         last_checked: now,
         remote_url,
+        staged_files: dirty.staged,
+        unstaged_files: dirty.unstaged,
+        untracked_files: dirty.untracked,
+        conflicted_files: dirty.conflicted,
+        upstream,
+        detached,
+        no_origin,
+        no_upstream,
+        upstream_gone,
+        conflicted: dirty.conflicted > 0,
+        default_branch: default_ref.as_deref().map(tracking::shorthand),
+        ahead_default,
+        behind_default,
+        remote_count,
+        last_commit_at,
+        last_commit_author,
+        last_fetched_at,
+        last_activity_at,
+        branch_since,
+        in_progress,
+        error_detail,
+        fingerprint,
+        kind,
+        parent_path,
+        nested_in,
+        submodule_count: submodules.total,
+        submodules_drifted: submodules.drifted,
+        submodules_uninitialized: submodules.uninitialized,
+        ..RepoInfo::default()
+    };
+    reevaluate(&mut info, rules);
+    info
+}
+
+/// Recompute `info`'s health from its stored fields and `rules`, for a repo
+/// whose status is unchanged but whose rules or time-based metrics may not be.
+pub fn reevaluate(info: &mut RepoInfo, rules: &[HealthRule]) {
+    info.health = determine_health(info);
+    health::apply(info, rules);
+}
+
+/// Carry a previously measured `ignored_large_files` over to a freshly read
+/// `info`, re-applying `rules` since a health rule may use the count.
+pub fn keep_ignored_large(info: &mut RepoInfo, measured: u32, rules: &[HealthRule]) {
+    if info.ignored_large_files != measured && info.health != RepoHealth::Error {
+        info.ignored_large_files = measured;
+        reevaluate(info, rules);
     }
 }
 
-fn get_branch_name(repo: &Repository) -> String {
-    repo.head()
-        .ok()
-        .and_then(|head| head.shorthand().map(String::from))
-        .unwrap_or_else(|| "HEAD (detached)".to_string())
+/// Count ignored files over `LARGE_IGNORED_BYTES`. Ignored directories are
+/// reported as single entries by libgit2 and then searched within the
+/// `IGNORED_WALK_*` limits. Too slow for every scan, so it's only run on demand.
+pub fn count_ignored_large(path: &Path) -> Result<u32, AppError> {
+    let repo = Repository::open(path)?;
+    let Some(workdir) = repo.workdir() else {
+        return Ok(0);
+    };
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(false)
+        .include_ignored(true)
+        .recurse_ignored_dirs(false);
+
+    let mut budget = IGNORED_WALK_ENTRIES;
+    let mut count = 0;
+    for entry in repo.statuses(Some(&mut opts))?.iter() {
+        if let (true, Some(rel)) = (entry.status().is_ignored(), entry.path()) {
+            count += count_large(&workdir.join(rel), IGNORED_WALK_DEPTH, &mut budget);
+        }
+    }
+    Ok(count)
+}
+
+/// The checked-out branch, or the short commit id when HEAD is detached.
+/// The bool is true for a detached HEAD.
+fn get_head(repo: &Repository) -> (String, bool) {
+    match repo.head() {
+        Ok(head) if head.is_branch() => (head.shorthand().unwrap_or("unknown").to_string(), false),
+        Ok(head) => {
+            let short = head
+                .target()
+                .map(|oid| oid.to_string()[..7].to_string())
+                .unwrap_or_else(|| "HEAD".to_string());
+            (short, true)
+        }
+        // An unborn branch (no commits yet) still has a name in HEAD
+        Err(_) => {
+            let name = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|head| head.symbolic_target().map(tracking::shorthand))
+                .unwrap_or_else(|| "unknown".to_string());
+            (name, false)
+        }
+    }
 }
 
 fn get_ahead_behind(repo: &Repository) -> (u32, u32) {
@@ -67,40 +212,108 @@ fn get_ahead_behind(repo: &Repository) -> (u32, u32) {
         Err(_) => return (0, 0),
     };
 
-    let local_oid = match head.target() {
-        Some(oid) => oid,
-        None => return (0, 0),
-    };
-
     let branch_name = match head.shorthand() {
-        Some(name) => name.to_string(),
-        None => return (0, 0),
+        Some(name) if head.is_branch() => name.to_string(),
+        _ => return (0, 0),
     };
 
-    let upstream_name = format!("refs/remotes/origin/{}", branch_name);
-    let upstream_ref = match repo.find_reference(&upstream_name) {
-        Ok(r) => r,
-        Err(_) => return (0, 0),
+    match tracking::upstream_ref(repo, &branch_name) {
+        Some(upstream_name) => ahead_behind_ref(repo, &upstream_name),
+        None => (0, 0),
+    }
+}
+
+/// Commits HEAD is ahead of and behind the ref named `other`.
+fn ahead_behind_ref(repo: &Repository, other: &str) -> (u32, u32) {
+    let local_oid = match repo.head().ok().and_then(|h| h.target()) {
+        Some(oid) => oid,
+        None => return (0, 0),
     };
 
-    let upstream_oid = match upstream_ref.target() {
+    let other_oid = match repo.find_reference(other).ok().and_then(|r| r.target()) {
         Some(oid) => oid,
         None => return (0, 0),
     };
 
-    repo.graph_ahead_behind(local_oid, upstream_oid)
+    repo.graph_ahead_behind(local_oid, other_oid)
         .map(|(a, b)| (a as u32, b as u32))
         .unwrap_or((0, 0))
 }
 
-fn get_dirty_count(repo: &Repository) -> u32 {
+/// Working tree changes by kind. A file both staged and modified again
+/// counts in both `staged` and `unstaged`; `total` counts it once.
+#[derive(Debug, Default)]
+struct DirtyCounts {
+    total: u32,
+    staged: u32,
+    unstaged: u32,
+    untracked: u32,
+    conflicted: u32,
+}
+
+/// Files of at least `LARGE_IGNORED_BYTES` at or under `path`, looking
+/// `depth` levels down and through at most `budget` directory entries.
+/// Symlinks and nested `.git` directories aren't followed.
+fn count_large(path: &Path, depth: usize, budget: &mut usize) -> u32 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if meta.is_file() {
+        return u32::from(meta.len() >= LARGE_IGNORED_BYTES);
+    }
+    if !meta.is_dir() || depth == 0 || path.ends_with(".git") {
+        return 0;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    let mut count = 0;
+    for entry in entries.flatten() {
+        if *budget == 0 {
+            break;
+        }
+        *budget -= 1;
+        count += count_large(&entry.path(), depth - 1, budget);
+    }
+    count
+}
+
+/// Count changes in one status pass. Untracked directories are reported as
+/// single entries rather than walked by libgit2.
+fn get_dirty_counts(repo: &Repository) -> Result<DirtyCounts, git2::Error> {
     let mut opts = git2::StatusOptions::new();
     opts.include_untracked(true)
         .recurse_untracked_dirs(false);
 
-    repo.statuses(Some(&mut opts))
-        .map(|statuses| statuses.len() as u32)
-        .unwrap_or(0)
+    let staged = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED
+        | Status::INDEX_TYPECHANGE;
+    let unstaged = Status::WT_MODIFIED
+        | Status::WT_DELETED
+        | Status::WT_RENAMED
+        | Status::WT_TYPECHANGE;
+
+    let mut counts = DirtyCounts::default();
+    for entry in repo.statuses(Some(&mut opts))?.iter() {
+        let status = entry.status();
+        counts.total += 1;
+        if status.is_conflicted() {
+            counts.conflicted += 1;
+            continue;
+        }
+        if status.intersects(staged) {
+            counts.staged += 1;
+        }
+        if status.intersects(unstaged) {
+            counts.unstaged += 1;
+        }
+        if status.is_wt_new() {
+            counts.untracked += 1;
+        }
+    }
+    Ok(counts)
 }
 
 fn get_stash_count(repo: &mut Repository) -> u32 {
@@ -112,14 +325,70 @@ fn get_stash_count(repo: &mut Repository) -> u32 {
     count
 }
 
+/// Commit time and author name of HEAD.
+fn get_last_commit(repo: &Repository) -> (Option<String>, Option<String>) {
+    let Some(commit) = repo.head().ok().and_then(|h| h.peel_to_commit().ok()) else {
+        return (None, None);
+    };
+    let at = to_rfc3339(commit.time().seconds());
+    let author = commit.author().name().map(String::from);
+    (at, author)
+}
+
+/// `FETCH_HEAD` is rewritten by every fetch (and pull), so its modification
+/// time is the last fetch. Linked worktrees write their own, so check the
+/// worktree's git dir before the shared one.
+fn get_last_fetch_time(repo: &Repository) -> Option<String> {
+    let modified = [repo.path(), repo.commondir()]
+        .iter()
+        .filter_map(|dir| std::fs::metadata(dir.join("FETCH_HEAD")).ok())
+        .find_map(|meta| meta.modified().ok())?;
+    Some(chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339())
+}
+
+/// Time of the newest HEAD reflog entry.
+fn get_last_activity_time(repo: &Repository) -> Option<String> {
+    let reflog = repo.reflog("HEAD").ok()?;
+    let seconds = reflog.get(0)?.committer().when().seconds();
+    to_rfc3339(seconds)
+}
+
+fn to_rfc3339(seconds: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(seconds, 0).map(|dt| dt.to_rfc3339())
+}
+
+/// When `branch` was last checked out: the newest "checkout: moving from X
+/// to <branch>" HEAD reflog entry, or the oldest entry if the branch has been
+/// checked out since the reflog began (e.g. since clone).
+fn get_branch_since(repo: &Repository, branch: &str) -> Option<String> {
+    let reflog = repo.reflog("HEAD").ok()?;
+    let suffix = format!(" to {}", branch);
+    let entry = reflog
+        .iter()
+        .find(|entry| {
+            entry
+                .message()
+                .is_some_and(|m| m.starts_with("checkout: moving from") && m.ends_with(&suffix))
+        })
+        .or_else(|| reflog.iter().next_back())?;
+    let seconds = entry.committer().when().seconds();
+    to_rfc3339(seconds)
+}
+
 fn get_origin_url(repo: &Repository) -> Option<String> {
     repo.find_remote("origin")
         .ok()
         .and_then(|r| r.url().map(String::from))
 }
 
-fn determine_health(ahead: u32, behind: u32, dirty_files: u32) -> RepoHealth {
-    if behind > 0 && (ahead > 0 || dirty_files > 0) {
+fn determine_health(info: &RepoInfo) -> RepoHealth {
+    let (ahead, behind, dirty_files) = (info.ahead, info.behind, info.dirty_files);
+
+    if info.error_detail.is_some() {
+        RepoHealth::Error
+    } else if info.in_progress.is_some() {
+        RepoHealth::InProgress
+    } else if behind > 0 && (ahead > 0 || dirty_files > 0) {
         RepoHealth::Diverged
     } else if dirty_files > 0 || ahead > 0 {
         RepoHealth::Dirty
@@ -127,3 +396,28 @@ fn determine_health(ahead: u32, behind: u32, dirty_files: u32) -> RepoHealth {
         RepoHealth::Clean
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn large_files_inside_ignored_dirs_are_counted_within_the_limits() {
+        let dir = std::env::temp_dir().join(format!("gitatlas-count-large-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("target/debug/deps")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        for file in ["target/debug/deps/big.rlib", "target/big.bin", ".git/big.pack"] {
+            fs::File::create(dir.join(file)).unwrap().set_len(LARGE_IGNORED_BYTES).unwrap();
+        }
+        fs::write(dir.join("target/small"), b"x").unwrap();
+
+        assert_eq!(count_large(&dir.join("target"), IGNORED_WALK_DEPTH, &mut 100), 2);
+        assert_eq!(count_large(&dir, IGNORED_WALK_DEPTH, &mut 100), 2);
+        assert_eq!(count_large(&dir.join("target"), 1, &mut 100), 1);
+        assert_eq!(count_large(&dir.join("target/big.bin"), 0, &mut 0), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
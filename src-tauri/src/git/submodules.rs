@@ -0,0 +1,238 @@
+use git2::{
+    Config, ConfigLevel, Repository, Submodule, SubmoduleIgnore, SubmoduleStatus,
+    SubmoduleUpdateOptions,
+};
+use std::fs;
+use std::path::Path;
+
+use super::operations::{cancelled_or, make_fetch_options};
+use super::progress::OpContext;
+use crate::db::models::SubmoduleInfo;
+use crate::error::AppError;
+
+/// Local changes inside a submodule's working tree.
+const DIRTY: SubmoduleStatus = SubmoduleStatus::WD_INDEX_MODIFIED
+    .union(SubmoduleStatus::WD_WD_MODIFIED)
+    .union(SubmoduleStatus::WD_UNTRACKED);
+
+/// Config keys `git submodule init` and friends write for a submodule.
+const CONFIG_KEYS: &[&str] = &["url", "active", "update", "branch", "fetchRecurseSubmodules", "ignore"];
+
+/// Submodule totals for a repo's status.
+#[derive(Debug, Default)]
+pub struct SubmoduleCounts {
+    pub total: u32,
+    /// Checked out at a different commit than the superproject records.
+    pub drifted: u32,
+    /// Not checked out (never initialized/updated, or deinitialized).
+    pub uninitialized: u32,
+}
+
+/// Every submodule of the repo at `path`, with its recorded and checked-out
+/// commits and state.
+pub fn list(path: &Path) -> Result<Vec<SubmoduleInfo>, AppError> {
+    let repo = Repository::open(path)?;
+    let gitmodules = repo
+        .workdir()
+        .map(|dir| dir.join(".gitmodules"))
+        .filter(|file| file.is_file())
+        .and_then(|file| Config::open(&file).ok());
+
+    let mut submodules: Vec<SubmoduleInfo> = repo
+        .submodules()?
+        .iter()
+        .map(|sub| describe(&repo, sub, gitmodules.as_ref()))
+        .collect();
+    submodules.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(submodules)
+}
+
+/// Count submodules that are drifted or not checked out, for `RepoInfo`.
+/// Repos without a `.gitmodules` cost nothing beyond one failed lookup.
+/// Runs on every scan, so each submodule's own working tree isn't read:
+/// `SubmoduleIgnore::Dirty` still compares its checked-out commit.
+pub fn counts(repo: &Repository) -> SubmoduleCounts {
+    let mut counts = SubmoduleCounts::default();
+    for sub in repo.submodules().unwrap_or_default() {
+        let Some(status) = sub.name().and_then(|name| repo.submodule_status(name, SubmoduleIgnore::Dirty).ok()) else {
+            continue;
+        };
+        counts.total += 1;
+        if status.contains(SubmoduleStatus::WD_UNINITIALIZED) {
+            counts.uninitialized += 1;
+        } else if status.contains(SubmoduleStatus::WD_MODIFIED) {
+            counts.drifted += 1;
+        }
+    }
+    counts
+}
+
+fn describe(repo: &Repository, sub: &Submodule, gitmodules: Option<&Config>) -> SubmoduleInfo {
+    let name = sub.name().unwrap_or_default().to_string();
+    let status = repo
+        .submodule_status(&name, SubmoduleIgnore::None)
+        .unwrap_or(SubmoduleStatus::empty());
+    let url = gitmodules
+        .and_then(|config| config.get_string(&format!("submodule.{}.url", name)).ok())
+        .or_else(|| sub.url().map(String::from));
+
+    SubmoduleInfo {
+        path: sub.path().to_string_lossy().to_string(),
+        out_of_sync: url.as_deref().is_some_and(|url| out_of_sync(repo, sub, &name, url)),
+        url,
+        branch: sub.branch().map(String::from),
+        // The index is what the next commit records, so a staged bump isn't drift
+        recorded_commit: sub.index_id().or_else(|| sub.head_id()).map(|id| id.to_string()),
+        checked_out_commit: sub.workdir_id().map(|id| id.to_string()),
+        initialized: status.contains(SubmoduleStatus::IN_CONFIG),
+        checked_out: !status.contains(SubmoduleStatus::WD_UNINITIALIZED),
+        drifted: status.contains(SubmoduleStatus::WD_MODIFIED),
+        dirty: status.intersects(DIRTY),
+        name,
+    }
+}
+
+/// Whether the URL in the superproject's config or the submodule's `origin`
+/// differs from `.gitmodules`, i.e. `git submodule sync` would change something.
+fn out_of_sync(repo: &Repository, sub: &Submodule, name: &str, url: &str) -> bool {
+    let expected = resolve_url(repo, url);
+    let configured = repo
+        .config()
+        .and_then(|config| config.get_string(&format!("submodule.{}.url", name)))
+        .ok();
+    let origin = sub
+        .open()
+        .ok()
+        .and_then(|sub_repo| sub_repo.find_remote("origin").ok()?.url().map(String::from));
+    configured.is_some_and(|u| u != expected) || origin.is_some_and(|u| u != expected)
+}
+
+/// Resolve a `./` or `../` submodule URL against the superproject's origin,
+/// as git does; absolute URLs are returned unchanged.
+fn resolve_url(repo: &Repository, url: &str) -> String {
+    if !url.starts_with("./") && !url.starts_with("../") {
+        return url.to_string();
+    }
+    let mut base = repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().map(String::from))
+        .or_else(|| repo.workdir().map(|dir| dir.to_string_lossy().to_string()))
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .to_string();
+
+    let mut rest = url;
+    let mut sep = '/';
+    loop {
+        if let Some(tail) = rest.strip_prefix("./") {
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("../") {
+            rest = tail;
+            // `host:path` remotes lose the path at ':' once it runs out
+            if let Some(i) = base.rfind(['/', ':']) {
+                sep = base[i..].chars().next().unwrap_or('/');
+                base.truncate(i);
+            }
+        } else {
+            break;
+        }
+    }
+    format!("{}{}{}", base, sep, rest)
+}
+
+/// The submodules named in `names`, or all of them when it's empty.
+fn select<'r>(repo: &'r Repository, names: &[String]) -> Result<Vec<Submodule<'r>>, AppError> {
+    let all = repo.submodules()?;
+    if let Some(missing) = names
+        .iter()
+        .find(|name| !all.iter().any(|sub| sub.name() == Some(name.as_str())))
+    {
+        return Err(AppError::General(format!("No submodule named '{}'", missing)));
+    }
+    Ok(all
+        .into_iter()
+        .filter(|sub| names.is_empty() || sub.name().is_some_and(|n| names.iter().any(|name| name == n)))
+        .collect())
+}
+
+/// Copy submodule URLs from `.gitmodules` into the repo's config, like
+/// `git submodule init`. Already initialized submodules are left alone.
+pub fn init(path: &Path, names: &[String]) -> Result<(), AppError> {
+    let repo = Repository::open(path)?;
+    for mut sub in select(&repo, names)? {
+        sub.init(false)?;
+    }
+    Ok(())
+}
+
+/// Update URLs in the repo's config and each submodule's `origin` from
+/// `.gitmodules`, like `git submodule sync`.
+pub fn sync(path: &Path, names: &[String]) -> Result<(), AppError> {
+    let repo = Repository::open(path)?;
+    for mut sub in select(&repo, names)? {
+        sub.sync()?;
+    }
+    Ok(())
+}
+
+/// Clone missing submodules and check each out at the recorded commit, like
+/// `git submodule update [--init]`. Transfer progress is reported under the
+/// superproject's path, labelled with the submodule's name.
+pub fn update(path: &Path, names: &[String], init: bool, ctx: &OpContext) -> Result<(), AppError> {
+    ctx.cancel.check()?;
+    let repo = Repository::open(path)?;
+    for mut sub in select(&repo, names)? {
+        ctx.cancel.check()?;
+        let label = sub.name().unwrap_or_default().to_string();
+        let mut opts = SubmoduleUpdateOptions::new();
+        opts.fetch(make_fetch_options(path, &label, ctx));
+        sub.update(init, Some(&mut opts))
+            .map_err(|e| cancelled_or(ctx, e))?;
+    }
+    Ok(())
+}
+
+/// Empty a submodule's working tree and drop its config, like
+/// `git submodule deinit`. Its git dir under `.git/modules` is kept so a
+/// later update doesn't need to clone again. Refuses when the submodule has
+/// local changes unless `force` is set, and always when its git dir is
+/// embedded in the working tree (an old-style submodule), since emptying the
+/// working tree would delete its history with it.
+pub fn deinit(path: &Path, name: &str, force: bool) -> Result<(), AppError> {
+    let repo = Repository::open(path)?;
+    let sub = repo.find_submodule(name)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| AppError::Unsupported("A bare repository has no submodules checked out".to_string()))?;
+    let dir = workdir.join(sub.path());
+
+    if dir.join(".git").is_dir() {
+        return Err(AppError::Conflict(format!(
+            "Submodule '{}' keeps its git directory in {}; deinitializing would delete its history. \
+             Run `git submodule absorbgitdirs` first",
+            name,
+            dir.display()
+        )));
+    }
+
+    let status = repo.submodule_status(name, SubmoduleIgnore::None)?;
+    if !force && status.intersects(DIRTY) {
+        return Err(AppError::Conflict(format!(
+            "Submodule '{}' has local changes; deinitialize with force to discard them",
+            name
+        )));
+    }
+
+    if dir.is_dir() {
+        fs::remove_dir_all(&dir)?;
+        fs::create_dir(&dir)?;
+    }
+
+    let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+    for key in CONFIG_KEYS {
+        // Keys that were never set are fine to miss
+        let _ = config.remove(&format!("submodule.{}.{}", name, key));
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use git2::Repository;
+
+use crate::error::AppError;
+
+/// Where a push of the current branch goes, resolved from git config.
+#[derive(Debug, Clone)]
+pub struct PushTarget {
+    pub remote: String,
+    /// Full destination ref on the remote, e.g. `refs/heads/main`.
+    pub dst: String,
+    /// The branch has no upstream yet and `push.autoSetupRemote` is on;
+    /// record this target as its upstream after a successful push.
+    pub set_upstream: bool,
+}
+
+/// The remote-tracking ref `branch` follows, from `branch.<name>.remote` and
+/// `branch.<name>.merge` (e.g. `refs/remotes/upstream/main`). `None` when no
+/// upstream is configured.
+pub fn upstream_ref(repo: &Repository, branch: &str) -> Option<String> {
+    repo.branch_upstream_name(&format!("refs/heads/{}", branch))
+        .ok()
+        .and_then(|buf| buf.as_str().map(String::from))
+}
+
+/// Strip `refs/remotes/` or `refs/heads/` from a full ref name.
+pub fn shorthand(full: &str) -> String {
+    full.strip_prefix("refs/remotes/")
+        .or_else(|| full.strip_prefix("refs/heads/"))
+        .unwrap_or(full)
+        .to_string()
+}
+
+/// The remote default branch ("trunk") to measure `branch` against, as a
+/// full remote-tracking ref. Remotes are tried in order: the one `branch`
+/// tracks, `upstream`, `origin`, then the rest. Each remote's `HEAD` symref
+/// (set by clone or `git remote set-head`) wins; failing that, a `main` or
+/// `master` branch on the first remote that has one.
+pub fn default_branch(repo: &Repository, branch: &str) -> Option<String> {
+    let mut remotes: Vec<String> = Vec::new();
+    let preferred = upstream_remote(repo, branch)
+        .into_iter()
+        .chain(["upstream".to_string(), "origin".to_string()]);
+    let configured = repo.remotes().ok()?;
+    let configured: Vec<String> = configured.iter().flatten().map(String::from).collect();
+    for remote in preferred.chain(configured.iter().cloned()) {
+        if configured.contains(&remote) && !remotes.contains(&remote) {
+            remotes.push(remote);
+        }
+    }
+
+    for remote in &remotes {
+        let head = format!("refs/remotes/{}/HEAD", remote);
+        if let Some(target) = repo
+            .find_reference(&head)
+            .ok()
+            .and_then(|r| r.symbolic_target().map(String::from))
+        {
+            return Some(target);
+        }
+    }
+    for remote in &remotes {
+        for name in ["main", "master"] {
+            let candidate = format!("refs/remotes/{}/{}", remote, name);
+            if repo.find_reference(&candidate).is_ok() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// The remote a branch tracks (`branch.<name>.remote`).
+fn upstream_remote(repo: &Repository, branch: &str) -> Option<String> {
+    repo.branch_upstream_remote(&format!("refs/heads/{}", branch))
+        .ok()
+        .and_then(|buf| buf.as_str().map(String::from))
+}
+
+/// Resolve where pushing `branch` should go, following git's rules: the
+/// remote is `branch.<name>.pushRemote`, then `remote.pushDefault`, then
+/// `branch.<name>.remote`, then `origin`; the destination branch follows
+/// `push.default` (`simple` when unset). A branch without an upstream gets
+/// one only when `push.autoSetupRemote` is set, as with `git push`.
+pub fn push_target(repo: &Repository, branch: &str) -> Result<PushTarget, AppError> {
+    let config = repo.config()?.snapshot()?;
+    let get = |key: &str| config.get_string(key).ok().filter(|v| !v.is_empty());
+
+    let tracked_remote = upstream_remote(repo, branch);
+    let merge = get(&format!("branch.{}.merge", branch));
+    let set_upstream =
+        tracked_remote.is_none() && config.get_bool("push.autoSetupRemote").unwrap_or(false);
+
+    let remote = get(&format!("branch.{}.pushRemote", branch))
+        .or_else(|| get("remote.pushDefault"))
+        .or_else(|| tracked_remote.clone())
+        .unwrap_or_else(|| "origin".to_string());
+    if repo.find_remote(&remote).is_err() {
+        return Err(if repo.remotes()?.is_empty() {
+            AppError::NoRemotes
+        } else {
+            AppError::RemoteNotFound(remote)
+        });
+    }
+
+    let same_name = format!("refs/heads/{}", branch);
+    let mode = get("push.default").unwrap_or_else(|| "simple".to_string());
+    // Pushing somewhere other than where the branch pulls from (a fork workflow)
+    let triangular = tracked_remote.as_deref() != Some(remote.as_str());
+
+    let dst = match mode.as_str() {
+        "nothing" => {
+            return Err(AppError::General(
+                "push.default is 'nothing'; push this branch from the command line".to_string(),
+            ));
+        }
+        // `matching` pushes every branch with a same-named remote branch; for
+        // the current branch that is the same as `current`.
+        "current" | "matching" => same_name,
+        "upstream" | "tracking" => match (merge, triangular) {
+            (Some(merge), false) => merge,
+            (Some(_), true) => {
+                return Err(AppError::General(format!(
+                    "push.default is 'upstream' but '{}' tracks a branch on another remote than '{}'",
+                    branch, remote
+                )));
+            }
+            (None, _) if set_upstream => same_name,
+            (None, _) => {
+                return Err(AppError::General(format!(
+                    "push.default is 'upstream' but '{}' has no upstream branch; set one or push from the command line",
+                    branch
+                )));
+            }
+        },
+        _ => match merge {
+            Some(merge) if !triangular && merge != same_name => {
+                return Err(AppError::General(format!(
+                    "'{}' tracks '{}' under a different name; set push.default to 'upstream' to push there",
+                    branch, merge
+                )));
+            }
+            _ => same_name,
+        },
+    };
+
+    Ok(PushTarget {
+        remote,
+        dst,
+        set_upstream,
+    })
+}
+
+/// Record `remote`/`merge` as the upstream of `branch`.
+pub fn set_upstream(repo: &Repository, branch: &str, remote: &str, merge: &str) -> Result<(), AppError> {
+    let mut config = repo.config()?;
+    config.set_str(&format!("branch.{}.remote", branch), remote)?;
+    config.set_str(&format!("branch.{}.merge", branch), merge)?;
+    Ok(())
+}
@@ -0,0 +1,312 @@
+use git2::{BranchType, Oid, Repository, StatusOptions};
+use std::fmt::Write;
+use std::path::Path;
+
+use super::tracking;
+use crate::db::models::{StashEntry, UnpushedBranch, UnpushedReport, UnpushedWork};
+use crate::error::AppError;
+
+/// The local-only work in the repo at `path`. A linked worktree shares its
+/// branches and stashes with the main repo, so only its HEAD and working tree
+/// are checked; a bare repo has no working tree to check. Errors are recorded
+/// on the result rather than returned, so one unreadable repo doesn't hide
+/// the rest of a report.
+pub fn read(path: &Path) -> UnpushedWork {
+    let mut work = read_nothing(path);
+    if let Err(e) = read_into(path, &mut work) {
+        work.error = Some(e.to_string());
+    }
+    work
+}
+
+/// An empty entry for the repo at `path`, before anything has been read.
+pub fn read_nothing(path: &Path) -> UnpushedWork {
+    UnpushedWork {
+        path: path.to_string_lossy().to_string(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+        ..Default::default()
+    }
+}
+
+fn read_into(path: &Path, work: &mut UnpushedWork) -> Result<(), AppError> {
+    let mut repo = Repository::open(path)?;
+    if !repo.is_worktree() {
+        work.branches = branches(&repo)?;
+        work.stashes = stashes(&mut repo)?;
+    }
+    work.detached_commits = detached_commits(&repo)?;
+    if !repo.is_bare() {
+        working_tree(&repo, work)?;
+    }
+    Ok(())
+}
+
+/// Branches ahead of their upstream, plus every branch without one (or whose
+/// upstream is gone), counting the commits no remote-tracking branch has.
+/// An upstream that is another local branch doesn't count as pushed.
+fn branches(repo: &Repository) -> Result<Vec<UnpushedBranch>, AppError> {
+    let mut branches = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let (Some(name), Some(tip)) = (branch.name()?, branch.get().target()) else {
+            continue;
+        };
+        let upstream = tracking::upstream_ref(repo, name).filter(|u| u.starts_with("refs/remotes/"));
+        let upstream_tip = upstream
+            .as_deref()
+            .and_then(|u| repo.find_reference(u).ok())
+            .and_then(|r| r.target());
+
+        let unpushed_commits = match upstream_tip {
+            Some(upstream_tip) => repo.graph_ahead_behind(tip, upstream_tip)?.0,
+            None => commits_outside(repo, tip, &["refs/remotes/"])?,
+        };
+        if upstream_tip.is_some() && unpushed_commits == 0 {
+            continue;
+        }
+        branches.push(UnpushedBranch {
+            name: name.to_string(),
+            upstream_gone: upstream.is_some() && upstream_tip.is_none(),
+            upstream: upstream.as_deref().map(tracking::shorthand),
+            unpushed_commits,
+            last_commit_at: repo
+                .find_commit(tip)
+                .ok()
+                .and_then(|c| chrono::DateTime::from_timestamp(c.time().seconds(), 0))
+                .map(|t| t.to_rfc3339()),
+        });
+    }
+    Ok(branches)
+}
+
+fn stashes(repo: &mut Repository) -> Result<Vec<StashEntry>, AppError> {
+    let mut stashes = Vec::new();
+    repo.stash_foreach(|index, message, _| {
+        stashes.push(StashEntry {
+            index,
+            message: message.to_string(),
+        });
+        true
+    })?;
+    Ok(stashes)
+}
+
+/// Commits a detached HEAD has that would be lost on the next checkout.
+fn detached_commits(repo: &Repository) -> Result<usize, AppError> {
+    if !repo.head_detached().unwrap_or(false) {
+        return Ok(0);
+    }
+    let Some(head) = repo.head()?.target() else {
+        return Ok(0);
+    };
+    commits_outside(repo, head, &["refs/heads/", "refs/remotes/", "refs/tags/"])
+}
+
+/// Commits reachable from `tip` but from no ref under any of `prefixes`.
+fn commits_outside(repo: &Repository, tip: Oid, prefixes: &[&str]) -> Result<usize, AppError> {
+    let mut walk = repo.revwalk()?;
+    walk.push(tip)?;
+    for reference in repo.references()? {
+        let reference = reference?;
+        let under_prefix = reference
+            .name()
+            .is_some_and(|name| prefixes.iter().any(|prefix| name.starts_with(prefix)));
+        // Symbolic refs such as `origin/HEAD` have no direct target and are skipped
+        if let (true, Some(oid)) = (under_prefix, reference.target()) {
+            walk.hide(oid)?;
+        }
+    }
+    Ok(walk.count())
+}
+
+/// Split uncommitted changes into changed tracked files and untracked ones.
+/// Untracked directories aren't walked, and ignored files aren't listed.
+fn working_tree(repo: &Repository, work: &mut UnpushedWork) -> Result<(), AppError> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .include_ignored(false);
+    for entry in repo.statuses(Some(&mut opts))?.iter() {
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        if entry.status().is_wt_new() {
+            work.untracked_files.push(path.to_string());
+        } else {
+            work.changed_files.push(path.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn has_work(work: &UnpushedWork) -> bool {
+    work.error.is_some()
+        || !work.branches.is_empty()
+        || work.detached_commits > 0
+        || !work.stashes.is_empty()
+        || !work.changed_files.is_empty()
+        || !work.untracked_files.is_empty()
+}
+
+/// Collect the repos in `works` that have something to lose, by path.
+pub fn report(mut works: Vec<UnpushedWork>) -> UnpushedReport {
+    let repos_checked = works.len();
+    works.retain(has_work);
+    works.sort_by(|a, b| a.path.cmp(&b.path));
+    UnpushedReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        repos_checked,
+        repos: works,
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
+
+/// Render the report as Markdown, one section per repo.
+pub fn to_markdown(report: &UnpushedReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Unpushed work\n");
+    let _ = writeln!(
+        out,
+        "Generated {}. Local-only work in {} of {} repos checked.",
+        report.generated_at,
+        report.repos.len(),
+        report.repos_checked
+    );
+
+    for work in &report.repos {
+        let _ = writeln!(out, "\n## {}\n\n`{}`\n", work.name, work.path);
+        if let Some(error) = &work.error {
+            let _ = writeln!(out, "- **Could not be read:** {}", error);
+        }
+        if !work.branches.is_empty() {
+            let _ = writeln!(out, "- **Branches**");
+            for branch in &work.branches {
+                let commits = plural(branch.unpushed_commits, "commit");
+                let state = match &branch.upstream {
+                    Some(upstream) if branch.upstream_gone => {
+                        format!("upstream `{}` is gone, {} on no remote", upstream, commits)
+                    }
+                    Some(upstream) => format!("{} ahead of `{}`", commits, upstream),
+                    None => format!("no upstream, {} on no remote", commits),
+                };
+                let _ = writeln!(out, "  - `{}` — {}", branch.name, state);
+            }
+        }
+        if work.detached_commits > 0 {
+            let _ = writeln!(
+                out,
+                "- **Detached HEAD** — {} on no branch",
+                plural(work.detached_commits, "commit")
+            );
+        }
+        if !work.stashes.is_empty() {
+            let _ = writeln!(out, "- **Stashes**");
+            for stash in &work.stashes {
+                let _ = writeln!(out, "  - `stash@{{{}}}` {}", stash.index, stash.message);
+            }
+        }
+        for (label, files) in [
+            ("Changed files", &work.changed_files),
+            ("Untracked files", &work.untracked_files),
+        ] {
+            if files.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "- **{}** ({})", label, files.len());
+            for file in files {
+                let _ = writeln!(out, "  - `{}`", file);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch(name: &str, upstream: Option<&str>, gone: bool, commits: usize) -> UnpushedBranch {
+        UnpushedBranch {
+            name: name.to_string(),
+            upstream: upstream.map(String::from),
+            upstream_gone: gone,
+            unpushed_commits: commits,
+            last_commit_at: None,
+        }
+    }
+
+    #[test]
+    fn report_keeps_only_repos_with_work_sorted_by_path() {
+        let report = report(vec![
+            UnpushedWork {
+                untracked_files: vec!["notes.txt".to_string()],
+                ..read_nothing(Path::new("/work/b"))
+            },
+            read_nothing(Path::new("/work/clean")),
+            UnpushedWork {
+                error: Some("broken".to_string()),
+                ..read_nothing(Path::new("/work/a"))
+            },
+        ]);
+        assert_eq!(report.repos_checked, 3);
+        let paths: Vec<_> = report.repos.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["/work/a", "/work/b"]);
+    }
+
+    #[test]
+    fn markdown_lists_each_kind_of_work() {
+        let report = UnpushedReport {
+            generated_at: "2026-01-01T00:00:00+00:00".to_string(),
+            repos_checked: 4,
+            repos: vec![UnpushedWork {
+                branches: vec![
+                    branch("main", Some("origin/main"), false, 2),
+                    branch("old", Some("origin/old"), true, 1),
+                    branch("wip", None, false, 3),
+                ],
+                detached_commits: 1,
+                stashes: vec![StashEntry {
+                    index: 0,
+                    message: "On main: experiment".to_string(),
+                }],
+                changed_files: vec!["src/lib.rs".to_string()],
+                untracked_files: vec!["a.txt".to_string(), "b.txt".to_string()],
+                ..read_nothing(Path::new("/work/cli"))
+            }],
+        };
+        let markdown = to_markdown(&report);
+
+        assert!(markdown.starts_with("# Unpushed work\n"));
+        assert!(markdown.contains("Local-only work in 1 of 4 repos checked."));
+        assert!(markdown.contains("\n## cli\n\n`/work/cli`\n"));
+        assert!(markdown.contains("  - `main` — 2 commits ahead of `origin/main`\n"));
+        assert!(markdown.contains("  - `old` — upstream `origin/old` is gone, 1 commit on no remote\n"));
+        assert!(markdown.contains("  - `wip` — no upstream, 3 commits on no remote\n"));
+        assert!(markdown.contains("- **Detached HEAD** — 1 commit on no branch\n"));
+        assert!(markdown.contains("  - `stash@{0}` On main: experiment\n"));
+        assert!(markdown.contains("- **Changed files** (1)\n  - `src/lib.rs`\n"));
+        assert!(markdown.contains("- **Untracked files** (2)\n  - `a.txt`\n  - `b.txt`\n"));
+        assert!(!markdown.contains("Could not be read"));
+    }
+
+    #[test]
+    fn markdown_reports_unreadable_repos() {
+        let report = UnpushedReport {
+            generated_at: String::new(),
+            repos_checked: 1,
+            repos: vec![UnpushedWork {
+                error: Some("not a git repository".to_string()),
+                ..read_nothing(Path::new("/work/gone"))
+            }],
+        };
+        let markdown = to_markdown(&report);
+        assert!(markdown.contains("- **Could not be read:** not a git repository\n"));
+        assert!(!markdown.contains("**Branches**"));
+    }
+}
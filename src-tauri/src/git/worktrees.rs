@@ -0,0 +1,170 @@
+use git2::{
+    BranchType, Repository, StatusOptions, Worktree, WorktreeAddOptions, WorktreeLockStatus,
+    WorktreePruneOptions,
+};
+use std::path::{Path, PathBuf};
+
+use super::status;
+use crate::db::models::WorktreeInfo;
+use crate::error::AppError;
+use crate::health::HealthRule;
+
+/// The main repository, whether `path` is its working tree or one of its
+/// linked worktrees.
+fn main_repo(path: &Path) -> Result<Repository, AppError> {
+    let repo = Repository::open(path)?;
+    if repo.is_worktree() {
+        Ok(Repository::open(repo.commondir())?)
+    } else {
+        Ok(repo)
+    }
+}
+
+/// libgit2 reports working tree paths with a trailing separator; strip it so
+/// paths match the ones discovery stores.
+fn clean_path(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+/// The main working tree (unless the repo is bare) followed by every linked
+/// worktree, each with its status read like a dashboard repo.
+pub fn list(path: &Path, rules: &[HealthRule]) -> Result<Vec<WorktreeInfo>, AppError> {
+    let repo = main_repo(path)?;
+    let mut worktrees = Vec::new();
+
+    if let Some(workdir) = repo.workdir() {
+        let workdir = clean_path(workdir);
+        worktrees.push(WorktreeInfo {
+            name: None,
+            path: workdir.to_string_lossy().to_string(),
+            is_main: true,
+            locked: false,
+            lock_reason: None,
+            prunable: false,
+            status: Some(status::get_repo_info(&workdir, rules)),
+        });
+    }
+
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        let lock_reason = match worktree.is_locked()? {
+            WorktreeLockStatus::Locked(reason) => Some(reason.unwrap_or_default()),
+            WorktreeLockStatus::Unlocked => None,
+        };
+        let wt_path = clean_path(worktree.path());
+        worktrees.push(WorktreeInfo {
+            name: Some(name.to_string()),
+            path: wt_path.to_string_lossy().to_string(),
+            is_main: false,
+            locked: lock_reason.is_some(),
+            lock_reason: lock_reason.filter(|reason| !reason.is_empty()),
+            prunable: worktree.is_prunable(None)?,
+            status: worktree
+                .validate()
+                .is_ok()
+                .then(|| status::get_repo_info(&wt_path, rules)),
+        });
+    }
+
+    Ok(worktrees)
+}
+
+/// Check `branch` out into a new worktree at `dest`, creating the branch from
+/// HEAD first when `create_branch` is set. The worktree is named after the
+/// last component of `dest`, as `git worktree add` does. Returns its path.
+pub fn add(path: &Path, dest: &Path, branch: &str, create_branch: bool) -> Result<PathBuf, AppError> {
+    let repo = main_repo(path)?;
+    if dest.exists() && dest.read_dir()?.next().is_some() {
+        return Err(AppError::General(format!(
+            "{} already exists and is not empty",
+            dest.display()
+        )));
+    }
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| AppError::General(format!("Invalid worktree path: {}", dest.display())))?;
+
+    let reference = if create_branch {
+        let head = repo.head()?.peel_to_commit()?;
+        repo.branch(branch, &head, false)?.into_reference()
+    } else {
+        repo.find_branch(branch, BranchType::Local)?.into_reference()
+    };
+
+    let mut opts = WorktreeAddOptions::new();
+    opts.reference(Some(&reference));
+    let worktree = repo.worktree(&name, dest, Some(&opts))?;
+    Ok(clean_path(worktree.path()))
+}
+
+/// Lock a worktree so `prune` leaves it alone, e.g. while it lives on a
+/// removable drive.
+pub fn lock(path: &Path, name: &str, reason: Option<&str>) -> Result<(), AppError> {
+    let repo = main_repo(path)?;
+    repo.find_worktree(name)?.lock(reason.filter(|r| !r.is_empty()))?;
+    Ok(())
+}
+
+pub fn unlock(path: &Path, name: &str) -> Result<(), AppError> {
+    let repo = main_repo(path)?;
+    repo.find_worktree(name)?.unlock()?;
+    Ok(())
+}
+
+/// Drop the metadata of worktrees whose working tree is gone, like
+/// `git worktree prune`. Locked worktrees are kept. Returns the paths of the
+/// worktrees pruned.
+pub fn prune(path: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let repo = main_repo(path)?;
+    let mut pruned = Vec::new();
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        if worktree.is_prunable(None)? {
+            worktree.prune(None)?;
+            pruned.push(clean_path(worktree.path()));
+        }
+    }
+    Ok(pruned)
+}
+
+/// Delete a worktree's working tree and metadata, like `git worktree
+/// remove`. Refuses a locked worktree, or one with modified or untracked
+/// files, unless `force` is set. Returns the removed worktree's path.
+pub fn remove(path: &Path, name: &str, force: bool) -> Result<PathBuf, AppError> {
+    let repo = main_repo(path)?;
+    let worktree = repo.find_worktree(name)?;
+
+    if !force {
+        if let WorktreeLockStatus::Locked(reason) = worktree.is_locked()? {
+            let reason = reason.filter(|r| !r.is_empty()).map(|r| format!(": {}", r));
+            return Err(AppError::Conflict(format!(
+                "Worktree '{}' is locked{}",
+                name,
+                reason.unwrap_or_default()
+            )));
+        }
+        if has_changes(&worktree)? {
+            return Err(AppError::Conflict(format!(
+                "Worktree '{}' has modified or untracked files",
+                name
+            )));
+        }
+    }
+
+    let mut opts = WorktreePruneOptions::new();
+    opts.valid(true).locked(force).working_tree(true);
+    worktree.prune(Some(&mut opts))?;
+    Ok(clean_path(worktree.path()))
+}
+
+fn has_changes(worktree: &Worktree) -> Result<bool, AppError> {
+    // A worktree whose directory is already gone has nothing to lose
+    let Ok(repo) = Repository::open_from_worktree(worktree) else {
+        return Ok(false);
+    };
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).include_ignored(false);
+    let clean = repo.statuses(Some(&mut opts))?.is_empty();
+    Ok(!clean)
+}
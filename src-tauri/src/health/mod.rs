@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::models::{HealthReason, RepoHealth, RepoInfo, Severity};
+
+/// A declarative health rule from the config file. A rule triggers when all
+/// of its conditions hold; triggered rules set the repo's severity and are
+/// listed as reasons on its card.
+///
+/// ```json
+/// { "id": "stale", "severity": "warning", "reason": "No commits in 90 days",
+///   "when": [{ "metric": "days_since_commit", "op": ">", "value": 90 }] }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthRule {
+    pub id: String,
+    pub severity: Severity,
+    pub reason: String,
+    pub when: Vec<Condition>,
+    /// Health state to show when this rule triggers. Only replaces a clean
+    /// or dirty state; errors, in-progress operations and divergence win.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<RepoHealth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Condition {
+    pub metric: Metric,
+    pub op: Op,
+    pub value: Value,
+}
+
+/// A value a rule can test. Booleans compare as 1 (true) and 0 (false).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    Ahead,
+    Behind,
+    DirtyFiles,
+    StagedFiles,
+    UnstagedFiles,
+    UntrackedFiles,
+    ConflictedFiles,
+    IgnoredLargeFiles,
+    StashCount,
+    AheadDefault,
+    BehindDefault,
+    RemoteCount,
+    DaysSinceCommit,
+    DaysOnBranch,
+    DaysSinceFetch,
+    DaysSinceActivity,
+    SubmodulesDrifted,
+    SubmodulesUninitialized,
+    OnDefaultBranch,
+    HasUpstream,
+    InProgress,
+    Detached,
+    NoOrigin,
+    UpstreamGone,
+    Conflicted,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Op {
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Gte,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Lte,
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Bool(bool),
+    Number(f64),
+}
+
+/// Rules used when the config file doesn't define any.
+pub fn default_rules() -> Vec<HealthRule> {
+    vec![
+        HealthRule {
+            id: "behind-trunk".to_string(),
+            severity: Severity::Warning,
+            reason: "50+ commits behind the default branch".to_string(),
+            when: vec![Condition {
+                metric: Metric::BehindDefault,
+                op: Op::Gte,
+                value: Value::Number(50.0),
+            }],
+            health: Some(RepoHealth::BehindTrunk),
+        },
+        HealthRule {
+            id: "many-stashes".to_string(),
+            severity: Severity::Warning,
+            reason: "More than 5 stashes".to_string(),
+            when: vec![Condition {
+                metric: Metric::StashCount,
+                op: Op::Gt,
+                value: Value::Number(5.0),
+            }],
+            health: None,
+        },
+        HealthRule {
+            id: "submodule-drift".to_string(),
+            severity: Severity::Warning,
+            reason: "Submodules not at the recorded commit".to_string(),
+            when: vec![Condition {
+                metric: Metric::SubmodulesDrifted,
+                op: Op::Gt,
+                value: Value::Number(0.0),
+            }],
+            health: None,
+        },
+        HealthRule {
+            id: "no-remote".to_string(),
+            severity: Severity::Info,
+            reason: "No remotes configured".to_string(),
+            when: vec![Condition {
+                metric: Metric::RemoteCount,
+                op: Op::Eq,
+                value: Value::Number(0.0),
+            }],
+            health: None,
+        },
+    ]
+}
+
+/// Evaluate `rules` against `info`, filling in its severity and reasons and
+/// applying the health override of the most severe triggered rule.
+pub fn apply(info: &mut RepoInfo, rules: &[HealthRule]) {
+    // Rules describe working repos; an unreadable one has no metrics to test
+    if info.health == RepoHealth::Error {
+        info.severity = Severity::Ok;
+        info.health_reasons.clear();
+        return;
+    }
+
+    let triggered: Vec<&HealthRule> = rules.iter().filter(|rule| matches(rule, info)).collect();
+
+    info.severity = triggered
+        .iter()
+        .map(|rule| rule.severity)
+        .max()
+        .unwrap_or(Severity::Ok);
+    info.health_reasons = triggered
+        .iter()
+        .map(|rule| HealthReason {
+            rule: rule.id.clone(),
+            severity: rule.severity,
+            message: rule.reason.clone(),
+        })
+        .collect();
+
+    if matches!(info.health, RepoHealth::Clean | RepoHealth::Dirty) {
+        if let Some(health) = triggered
+            .iter()
+            .filter(|rule| rule.health.is_some())
+            .max_by_key(|rule| rule.severity)
+            .and_then(|rule| rule.health.clone())
+        {
+            info.health = health;
+        }
+    }
+}
+
+fn matches(rule: &HealthRule, info: &RepoInfo) -> bool {
+    !rule.when.is_empty()
+        && rule.when.iter().all(|cond| {
+            // A metric with no data (e.g. no commits yet) never matches
+            let Some(actual) = metric(info, cond.metric) else {
+                return false;
+            };
+            let expected = match cond.value {
+                Value::Bool(b) => bool_value(b),
+                Value::Number(n) => n,
+            };
+            match cond.op {
+                Op::Gt => actual > expected,
+                Op::Gte => actual >= expected,
+                Op::Lt => actual < expected,
+                Op::Lte => actual <= expected,
+                Op::Eq => actual == expected,
+                Op::Ne => actual != expected,
+            }
+        })
+}
+
+fn metric(info: &RepoInfo, metric: Metric) -> Option<f64> {
+    let value = match metric {
+        Metric::Ahead => info.ahead as f64,
+        Metric::Behind => info.behind as f64,
+        Metric::DirtyFiles => info.dirty_files as f64,
+        Metric::StagedFiles => info.staged_files as f64,
+        Metric::UnstagedFiles => info.unstaged_files as f64,
+        Metric::UntrackedFiles => info.untracked_files as f64,
+        Metric::ConflictedFiles => info.conflicted_files as f64,
+        Metric::IgnoredLargeFiles => info.ignored_large_files as f64,
+        Metric::StashCount => info.stash_count as f64,
+        Metric::AheadDefault => info.ahead_default as f64,
+        Metric::BehindDefault => info.behind_default as f64,
+        Metric::RemoteCount => info.remote_count as f64,
+        Metric::DaysSinceCommit => days_since(info.last_commit_at.as_deref()?)?,
+        Metric::DaysOnBranch => days_since(info.branch_since.as_deref()?)?,
+        Metric::DaysSinceFetch => days_since(info.last_fetched_at.as_deref()?)?,
+        Metric::DaysSinceActivity => days_since(info.last_activity_at.as_deref()?)?,
+        Metric::SubmodulesDrifted => info.submodules_drifted as f64,
+        Metric::SubmodulesUninitialized => info.submodules_uninitialized as f64,
+        Metric::OnDefaultBranch => bool_value(on_default_branch(info)?),
+        Metric::HasUpstream => bool_value(info.upstream.is_some()),
+        Metric::InProgress => bool_value(info.in_progress.is_some()),
+        Metric::Detached => bool_value(info.detached),
+        Metric::NoOrigin => bool_value(info.no_origin),
+        Metric::UpstreamGone => bool_value(info.upstream_gone),
+        Metric::Conflicted => bool_value(info.conflicted),
+    };
+    Some(value)
+}
+
+fn bool_value(b: bool) -> f64 {
+    if b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Whether the checked-out branch is the default branch, comparing
+/// `main` against `origin/main`. Unknown when there is no default branch.
+fn on_default_branch(info: &RepoInfo) -> Option<bool> {
+    let default = info.default_branch.as_deref()?;
+    let (_, name) = default.split_once('/')?;
+    Some(name == info.branch)
+}
+
+fn days_since(timestamp: &str) -> Option<f64> {
+    let then = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let elapsed = chrono::Utc::now().signed_duration_since(then);
+    Some(elapsed.num_seconds() as f64 / 86_400.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(json: &str) -> HealthRule {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn most_severe_triggered_rule_sets_severity() {
+        let rules = [
+            rule(r#"{ "id": "ahead", "severity": "info", "reason": "Ahead",
+                      "when": [{ "metric": "ahead", "op": ">", "value": 0 }] }"#),
+            rule(r#"{ "id": "stashes", "severity": "warning", "reason": "Stashes",
+                      "when": [{ "metric": "stash_count", "op": ">=", "value": 2 }] }"#),
+            rule(r#"{ "id": "behind", "severity": "error", "reason": "Behind",
+                      "when": [{ "metric": "behind", "op": ">", "value": 0 }] }"#),
+        ];
+        let mut info = RepoInfo {
+            ahead: 3,
+            stash_count: 2,
+            ..Default::default()
+        };
+        apply(&mut info, &rules);
+        assert_eq!(info.severity, Severity::Warning);
+        let ids: Vec<_> = info.health_reasons.iter().map(|r| r.rule.as_str()).collect();
+        assert_eq!(ids, ["ahead", "stashes"]);
+    }
+
+    #[test]
+    fn every_condition_must_hold() {
+        let rules = [rule(
+            r#"{ "id": "r", "severity": "warning", "reason": "r",
+                 "when": [{ "metric": "dirty_files", "op": ">", "value": 0 },
+                          { "metric": "detached", "op": "==", "value": true }] }"#,
+        )];
+        let mut info = RepoInfo {
+            dirty_files: 1,
+            ..Default::default()
+        };
+        apply(&mut info, &rules);
+        assert_eq!(info.severity, Severity::Ok);
+
+        info.detached = true;
+        apply(&mut info, &rules);
+        assert_eq!(info.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn rule_without_conditions_never_triggers() {
+        let mut info = RepoInfo::default();
+        apply(&mut info, &[rule(r#"{ "id": "r", "severity": "error", "reason": "r", "when": [] }"#)]);
+        assert!(info.health_reasons.is_empty());
+    }
+
+    #[test]
+    fn metric_without_data_never_matches() {
+        let rules = [rule(
+            r#"{ "id": "r", "severity": "warning", "reason": "r",
+                 "when": [{ "metric": "days_since_commit", "op": ">=", "value": 0 }] }"#,
+        )];
+        let mut info = RepoInfo::default();
+        apply(&mut info, &rules);
+        assert!(info.health_reasons.is_empty());
+
+        info.last_commit_at = Some((chrono::Utc::now() - chrono::Duration::days(2)).to_rfc3339());
+        apply(&mut info, &rules);
+        assert_eq!(info.health_reasons.len(), 1);
+    }
+
+    #[test]
+    fn on_default_branch_compares_without_the_remote() {
+        let rules = [rule(
+            r#"{ "id": "r", "severity": "info", "reason": "r",
+                 "when": [{ "metric": "on_default_branch", "op": "==", "value": false }] }"#,
+        )];
+        let mut info = RepoInfo {
+            branch: "main".to_string(),
+            default_branch: Some("origin/main".to_string()),
+            ..Default::default()
+        };
+        apply(&mut info, &rules);
+        assert!(info.health_reasons.is_empty());
+
+        info.branch = "feature".to_string();
+        apply(&mut info, &rules);
+        assert_eq!(info.health_reasons.len(), 1);
+    }
+
+    #[test]
+    fn health_override_only_replaces_clean_or_dirty() {
+        let rules = default_rules();
+        let mut info = RepoInfo {
+            behind_default: 60,
+            remote_count: 1,
+            health: RepoHealth::Dirty,
+            ..Default::default()
+        };
+        apply(&mut info, &rules);
+        assert_eq!(info.health, RepoHealth::BehindTrunk);
+
+        info.health = RepoHealth::Diverged;
+        apply(&mut info, &rules);
+        assert_eq!(info.health, RepoHealth::Diverged);
+    }
+
+    #[test]
+    fn unreadable_repo_gets_no_reasons() {
+        let mut info = RepoInfo {
+            health: RepoHealth::Error,
+            ..Default::default()
+        };
+        apply(&mut info, &default_rules());
+        assert_eq!(info.severity, Severity::Ok);
+        assert!(info.health_reasons.is_empty());
+    }
+}
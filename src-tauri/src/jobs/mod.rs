@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Semaphore;
+use tokio::task::{JoinError, JoinSet};
+
+use crate::git::clone::RemoteUrl;
+use crate::git::progress::CancelToken;
+
+/// Cancel tokens for in-flight operations, keyed by the id the frontend
+/// supplied when starting them.
+#[derive(Default)]
+pub struct Operations {
+    tokens: Mutex<HashMap<String, CancelToken>>,
+}
+
+impl Operations {
+    pub fn register(&self, id: &str) -> CancelToken {
+        let token = CancelToken::default();
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), token.clone());
+        token
+    }
+
+    pub fn finish(&self, id: &str) {
+        self.tokens.lock().unwrap().remove(id);
+    }
+
+    /// Cancel the operation with the given id. Returns false if it isn't running.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.tokens.lock().unwrap().get(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Concurrency limits for a bulk run. `per_host` caps simultaneous jobs
+/// against the same remote host so a single server isn't hammered.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub total: usize,
+    pub per_host: usize,
+}
+
+/// One unit of work in a bulk run.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub path: PathBuf,
+    pub remote_url: Option<String>,
+}
+
+/// Run `work` for every job on blocking worker threads, honouring `limits`.
+/// `on_result` is called on the async side as each job finishes, in
+/// completion order, so callers can persist and stream results immediately.
+/// A job that panics still gets a result, built by `failed` from its path and
+/// the panic message. Returns one result per job, in completion order.
+pub async fn run<T, W, E, F>(
+    jobs: Vec<Job>,
+    limits: Limits,
+    work: W,
+    failed: E,
+    mut on_result: F,
+) -> Vec<T>
+where
+    T: Send + 'static,
+    W: Fn(&Path) -> T + Send + Sync + 'static,
+    E: Fn(&Path, String) -> T,
+    F: FnMut(&T),
+{
+    let work = Arc::new(work);
+    let total = Arc::new(Semaphore::new(limits.total.max(1)));
+    let mut hosts: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    let mut set = JoinSet::new();
+    let mut paths: HashMap<tokio::task::Id, PathBuf> = HashMap::new();
+
+    for job in jobs {
+        // Repos without a recognisable remote only count against the total limit
+        let host_limit = job.remote_url.as_deref().and_then(RemoteUrl::parse).map(|remote| {
+            hosts
+                .entry(remote.host)
+                .or_insert_with(|| Arc::new(Semaphore::new(limits.per_host.max(1))))
+                .clone()
+        });
+        let total = total.clone();
+        let work = work.clone();
+        let path = job.path.clone();
+
+        let handle = set.spawn(async move {
+            // Take the host permit first so jobs queued behind a busy host
+            // don't hold a global slot while they wait.
+            let _host = match host_limit {
+                Some(limit) => Some(limit.acquire_owned().await.expect("semaphore closed")),
+                None => None,
+            };
+            let _slot = total.acquire_owned().await.expect("semaphore closed");
+            tokio::task::spawn_blocking(move || work(&job.path)).await
+        });
+        paths.insert(handle.id(), path);
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = set.join_next_with_id().await {
+        let result = match joined {
+            Ok((_, Ok(result))) => result,
+            Ok((id, Err(e))) => failed(&paths[&id], panic_message(e)),
+            Err(e) => failed(&paths[&e.id()], panic_message(e)),
+        };
+        on_result(&result);
+        results.push(result);
+    }
+    results
+}
+
+fn panic_message(e: JoinError) -> String {
+    if !e.is_panic() {
+        return format!("Job did not finish: {}", e);
+    }
+    let payload = e.into_panic();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    format!("Internal error: {}", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn panicking_job_still_gets_a_result() {
+        let jobs = ["/a", "/boom", "/c"]
+            .iter()
+            .map(|path| Job {
+                path: PathBuf::from(path),
+                remote_url: None,
+            })
+            .collect();
+        let limits = Limits {
+            total: 2,
+            per_host: 1,
+        };
+        let work = |path: &Path| {
+            if path == Path::new("/boom") {
+                panic!("exploded");
+            }
+            Ok(path.to_path_buf())
+        };
+        let failed = |path: &Path, message: String| Err((path.to_path_buf(), message));
+
+        let mut seen = 0;
+        let results: Vec<Result<PathBuf, (PathBuf, String)>> =
+            run(jobs, limits, work, failed, |_| seen += 1).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(seen, 3);
+        let failures: Vec<_> = results.into_iter().filter_map(Result::err).collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, PathBuf::from("/boom"));
+        assert!(failures[0].1.contains("exploded"));
+    }
+}
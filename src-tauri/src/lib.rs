@@ -3,34 +3,121 @@ mod commands;
 mod db;
 mod error;
 mod git;
+mod health;
+mod jobs;
+mod manifest;
 mod scanner;
+mod watcher;
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tauri::Manager;
 
 use db::Database;
 
 pub struct AppState {
     pub db: Database,
+    pub operations: jobs::Operations,
+    /// Running filesystem watcher, when live updates are on.
+    pub watcher: Mutex<Option<watcher::RepoWatcher>>,
+}
+
+/// How long status snapshots are kept before being pruned at startup.
+const SNAPSHOT_RETENTION_DAYS: i64 = 365;
+
+/// Open the on-disk database, importing any repos left in the legacy
+/// `cache.json` and pruning old status snapshots. Falls back to an in-memory database when no home
+/// directory is available.
+fn open_database() -> Result<Database, error::AppError> {
+    let db = match cache::database_path() {
+        Some(path) => Database::open(&path)?,
+        None => Database::new_in_memory()?,
+    };
+
+    // cache.json is only removed once the import has committed, so a failed
+    // import is retried on the next launch
+    if let Some(legacy) = cache::read_legacy_repos() {
+        db.upsert_repos(&legacy)?;
+        cache::remove_legacy_repos();
+    }
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(SNAPSHOT_RETENTION_DAYS);
+    db.prune_snapshots(&cutoff.to_rfc3339())?;
+
+    Ok(db)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let db = Database::new_in_memory().expect("Failed to initialize database");
-    let state = AppState { db };
+    let db = open_database().expect("Failed to initialize database");
+    let state = AppState {
+        db,
+        operations: jobs::Operations::default(),
+        watcher: Mutex::new(None),
+    };
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .manage(state)
+        .setup(|app| {
+            if cache::load_config().watch {
+                let paths: Vec<PathBuf> = app
+                    .state::<AppState>()
+                    .db
+                    .get_all_repos()?
+                    .into_iter()
+                    .map(|repo| PathBuf::from(repo.path))
+                    .collect();
+                // Setting up watches walks every repo; don't hold up the window for it
+                let app = app.handle().clone();
+                std::thread::spawn(move || {
+                    let Ok(mut watcher) = watcher::RepoWatcher::start(app.clone()) else {
+                        return;
+                    };
+                    watcher.sync(&paths);
+                    let state = app.state::<AppState>();
+                    let mut slot = state.watcher.lock().unwrap();
+                    // Live updates may have been switched off, or on again, meanwhile
+                    if slot.is_none() && cache::load_config().watch {
+                        *slot = Some(watcher);
+                    }
+                });
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::scan::scan_directories,
             commands::scan::get_scan_roots,
             commands::scan::set_scan_roots,
+            commands::scan::get_config_problems,
             commands::scan::load_cached_repos,
+            commands::watch::start_watching,
+            commands::watch::stop_watching,
+            commands::watch::is_watching,
             commands::status::get_all_repos,
+            commands::status::query_repos,
             commands::status::get_repo_status,
+            commands::status::measure_ignored_large_files,
+            commands::status::get_status_history,
+            commands::status::get_repo_trends,
+            commands::status::diagnose_repo,
+            commands::status::get_unpushed_report,
+            commands::status::export_unpushed_report,
             commands::operations::fetch_all,
             commands::operations::pull_all,
             commands::operations::fetch_repo,
             commands::operations::pull_rebase_repo,
             commands::operations::push_repo,
+            commands::operations::update_submodules,
+            commands::operations::get_clone_destination,
+            commands::operations::clone_repo,
+            commands::manifest::export_manifest,
+            commands::manifest::sync_manifest,
+            commands::operations::continue_in_progress,
+            commands::operations::skip_in_progress,
+            commands::operations::abort_in_progress,
+            commands::operations::cancel_operation,
             commands::detail::get_commit_log,
             commands::detail::get_commit_diff,
             commands::detail::get_file_changes,
@@ -56,6 +143,16 @@ pub fn run() {
             commands::detail::add_remote,
             commands::detail::remove_remote,
             commands::detail::rename_remote,
+            commands::detail::get_worktrees,
+            commands::detail::add_worktree,
+            commands::detail::lock_worktree,
+            commands::detail::unlock_worktree,
+            commands::detail::prune_worktrees,
+            commands::detail::remove_worktree,
+            commands::detail::get_submodules,
+            commands::detail::init_submodules,
+            commands::detail::sync_submodules,
+            commands::detail::deinit_submodule,
             commands::detail::get_git_profile,
             commands::detail::set_git_profile,
             commands::detail::squash_commits,
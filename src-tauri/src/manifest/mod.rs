@@ -0,0 +1,661 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::db::models::{
+    ManifestEntryResult, ManifestFormat, ManifestOutcome, RemoteInfo, RepoInfo, RepoKind,
+};
+use crate::error::AppError;
+use crate::git;
+use crate::git::clone::RemoteUrl;
+use crate::git::discovery::{self, ScanRoot};
+use crate::git::progress::OpContext;
+use crate::health::HealthRule;
+
+/// Version of the native manifest format written by `export`.
+const VERSION: u32 = 1;
+
+/// A workspace manifest: every repo on one machine, described well enough to
+/// clone them all again on another.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub repos: Vec<ManifestRepo>,
+}
+
+/// One repo in a manifest, whichever format it was read from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestRepo {
+    /// Relative to the scan root the repo lives under, with `/` separators,
+    /// or absolute for a repo outside every root (only synced when under the
+    /// target root). Unset for a bare URL, which goes wherever the clone
+    /// layout puts it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub remotes: Vec<RemoteInfo>,
+    /// Branch name without the remote, e.g. `main`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Why this entry can't be synced, found while parsing (e.g. a `repo`
+    /// remote whose relative fetch URL can't be resolved).
+    #[serde(skip)]
+    pub problem: Option<String>,
+}
+
+impl ManifestRepo {
+    /// The remote to clone from: `origin` if listed, else the first remote.
+    pub fn clone_remote(&self) -> Option<&RemoteInfo> {
+        self.remotes
+            .iter()
+            .find(|r| r.name == "origin")
+            .or(self.remotes.first())
+    }
+
+    pub fn clone_url(&self) -> Option<&str> {
+        self.clone_remote().map(|r| r.url.as_str())
+    }
+}
+
+// ── Export ──
+
+/// Describe `repos` as a native manifest. Worktrees and submodules are left
+/// out; they come back with their main repo or superproject.
+pub fn export(
+    repos: &[RepoInfo],
+    tags: &HashMap<String, Vec<String>>,
+    roots: &[ScanRoot],
+) -> Manifest {
+    let repos = repos
+        .iter()
+        .filter(|repo| !matches!(repo.kind, RepoKind::Worktree | RepoKind::Submodule))
+        .map(|repo| {
+            let path = Path::new(&repo.path);
+            ManifestRepo {
+                path: Some(relative_to_roots(path, roots)),
+                remotes: git::detail::get_remotes(path).unwrap_or_default(),
+                default_branch: repo.default_branch.as_deref().map(branch_name),
+                tags: tags.get(&repo.path).cloned().unwrap_or_default(),
+                problem: None,
+            }
+        })
+        .collect();
+    Manifest {
+        version: VERSION,
+        repos,
+    }
+}
+
+/// `path` relative to the deepest root containing it, or unchanged when no
+/// root does.
+fn relative_to_roots(path: &Path, roots: &[ScanRoot]) -> String {
+    let relative = roots
+        .iter()
+        .filter_map(|root| path.strip_prefix(&root.path).ok())
+        .filter(|rel| rel.components().next().is_some())
+        .min_by_key(|rel| rel.components().count());
+    match relative {
+        Some(rel) => rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        None => path.to_string_lossy().to_string(),
+    }
+}
+
+/// `origin/main` → `main`; the manifest records branches without the remote
+/// because the remote may be named differently on the other machine.
+fn branch_name(tracking: &str) -> String {
+    tracking
+        .split_once('/')
+        .map_or(tracking, |(_, name)| name)
+        .to_string()
+}
+
+// ── Import ──
+
+/// The `origin` URL of the git repo holding the manifest at `file`, as in a
+/// `repo` checkout's `.repo/manifests`. Relative `repo` fetch URLs resolve
+/// against it.
+pub fn manifest_url(file: &Path) -> Option<String> {
+    let repo = Repository::discover(file.parent()?).ok()?;
+    let origin = repo.find_remote("origin").ok()?;
+    origin.url().map(String::from)
+}
+
+/// Read a manifest in any supported format, told apart by its first
+/// character: `{` for native JSON, `<` for a Google `repo` manifest, and
+/// anything else for a list of URLs. `manifest_url` is where the manifest
+/// itself came from, if known.
+pub fn parse(
+    text: &str,
+    manifest_url: Option<&str>,
+) -> Result<(ManifestFormat, Vec<ManifestRepo>), AppError> {
+    let trimmed = text.trim_start();
+    if trimmed.starts_with('{') {
+        Ok((ManifestFormat::Native, parse_native(trimmed)?))
+    } else if trimmed.starts_with('<') {
+        Ok((ManifestFormat::RepoXml, parse_repo_xml(trimmed, manifest_url)?))
+    } else {
+        Ok((ManifestFormat::UrlList, parse_url_list(trimmed)?))
+    }
+}
+
+fn parse_native(text: &str) -> Result<Vec<ManifestRepo>, AppError> {
+    let manifest: Manifest = serde_json::from_str(text)
+        .map_err(|e| AppError::General(format!("Invalid manifest: {}", e)))?;
+    if manifest.version > VERSION {
+        return Err(AppError::Unsupported(format!(
+            "Manifest version {} is newer than this build supports ({})",
+            manifest.version, VERSION
+        )));
+    }
+    Ok(manifest.repos)
+}
+
+/// A Google `repo` manifest: `<remote>`s with a fetch base, an optional
+/// `<default>` remote and revision, and a `<project>` per repo whose URL is
+/// the fetch base plus the project name. Groups become tags. A relative
+/// fetch base (`..`) resolves against `manifest_url`; without one, the
+/// projects using it are reported as unsyncable rather than failing the file.
+fn parse_repo_xml(text: &str, manifest_url: Option<&str>) -> Result<Vec<ManifestRepo>, AppError> {
+    let doc = roxmltree::Document::parse(text)
+        .map_err(|e| AppError::General(format!("Invalid repo manifest: {}", e)))?;
+    let manifest = doc.root_element();
+    if !manifest.has_tag_name("manifest") {
+        return Err(AppError::General(
+            "Invalid repo manifest: the root element isn't <manifest>".to_string(),
+        ));
+    }
+
+    let mut fetch_bases = HashMap::new();
+    let mut default_remote = None;
+    let mut default_revision = None;
+    for node in manifest.children().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "remote" => {
+                if let (Some(name), Some(fetch)) = (node.attribute("name"), node.attribute("fetch")) {
+                    fetch_bases.insert(name, fetch);
+                }
+            }
+            "default" => {
+                default_remote = node.attribute("remote");
+                default_revision = node.attribute("revision");
+            }
+            "include" => {
+                return Err(AppError::Unsupported(format!(
+                    "The manifest includes {}; sync that file separately",
+                    node.attribute("name").unwrap_or("another manifest")
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    let mut repos = Vec::new();
+    for project in manifest.children().filter(|n| n.has_tag_name("project")) {
+        let name = project
+            .attribute("name")
+            .ok_or_else(|| AppError::General("Invalid repo manifest: a project has no name".to_string()))?;
+        let remote = project.attribute("remote").or(default_remote).ok_or_else(|| {
+            AppError::General(format!("Project '{}' names no remote and there is no default", name))
+        })?;
+        let fetch = fetch_bases
+            .get(remote)
+            .ok_or_else(|| AppError::General(format!("Project '{}' uses unknown remote '{}'", name, remote)))?;
+        let (fetch, problem) = if fetch.starts_with('.') {
+            match manifest_url.and_then(|base| resolve_relative(base, fetch)) {
+                Some(resolved) => (resolved, None),
+                None => (
+                    fetch.to_string(),
+                    Some(format!(
+                        "Remote '{}' has a fetch URL relative to the manifest ({}), and the manifest's own URL \
+                         is unknown; keep it in a clone of the manifest repo or use an absolute URL",
+                        remote, fetch
+                    )),
+                ),
+            }
+        } else {
+            (fetch.to_string(), None)
+        };
+
+        let revision = project.attribute("revision").or(default_revision);
+        repos.push(ManifestRepo {
+            path: Some(project.attribute("path").unwrap_or(name).to_string()),
+            remotes: vec![RemoteInfo {
+                name: remote.to_string(),
+                url: format!("{}/{}", fetch.trim_end_matches('/'), name),
+            }],
+            default_branch: revision.and_then(revision_branch),
+            tags: project
+                .attribute("groups")
+                .unwrap_or_default()
+                .split([',', ' '])
+                .filter(|group| !group.is_empty())
+                .map(String::from)
+                .collect(),
+            problem,
+        });
+    }
+    Ok(repos)
+}
+
+/// Resolve a relative `repo` fetch URL such as `..` against the manifest's
+/// URL, treating the manifest URL as a directory the way `repo` does:
+/// `https://host/platform/manifest` with `..` gives `https://host/platform`.
+/// Handles scp-like URLs too. `None` when the path climbs above the host.
+fn resolve_relative(manifest_url: &str, fetch: &str) -> Option<String> {
+    let manifest_url = manifest_url.trim_end_matches('/');
+    let (prefix, path, separator) = match manifest_url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+            (format!("{}://{}", scheme, host), path, '/')
+        }
+        None => {
+            let (host, path) = manifest_url.split_once(':')?;
+            (host.to_string(), path, ':')
+        }
+    };
+
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in fetch.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    if segments.is_empty() {
+        return (separator == '/').then_some(prefix);
+    }
+    Some(format!("{}{}{}", prefix, separator, segments.join("/")))
+}
+
+/// The branch a `repo` revision names; tags and commit ids aren't branches.
+fn revision_branch(revision: &str) -> Option<String> {
+    if revision.starts_with("refs/tags/")
+        || (revision.len() == 40 && revision.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        return None;
+    }
+    Some(revision.strip_prefix("refs/heads/").unwrap_or(revision).to_string())
+}
+
+/// One remote URL per line, optionally followed by a path relative to the
+/// root. Blank lines and `#` comments are skipped.
+fn parse_url_list(text: &str) -> Result<Vec<ManifestRepo>, AppError> {
+    let mut repos = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let url = fields.next().unwrap_or_default();
+        if RemoteUrl::parse(url).is_none() {
+            return Err(AppError::General(format!(
+                "Line {}: not a remote repository URL: {}",
+                index + 1,
+                url
+            )));
+        }
+        repos.push(ManifestRepo {
+            path: fields.next().map(String::from),
+            remotes: vec![RemoteInfo {
+                name: "origin".to_string(),
+                url: url.to_string(),
+            }],
+            default_branch: None,
+            tags: Vec::new(),
+            problem: None,
+        });
+    }
+    Ok(repos)
+}
+
+// ── Sync ──
+
+/// How the manifest's repos stand on disk before anything is cloned.
+#[derive(Default)]
+pub struct Survey {
+    /// Entries that are settled: present, mismatched or unusable.
+    pub results: Vec<ManifestEntryResult>,
+    /// Repos found on disk, with the tags the manifest gives them.
+    pub found: Vec<(RepoInfo, Vec<String>)>,
+    /// Entries with nothing on disk yet, by destination.
+    pub missing: Vec<(PathBuf, ManifestRepo)>,
+}
+
+/// Check each entry's destination under `root` against the manifest.
+/// Only the local filesystem is touched. When several entries go to the
+/// same directory, the first one wins and the rest are reported as
+/// duplicates, so two clones never race for one destination.
+pub fn survey(entries: Vec<ManifestRepo>, root: &Path, layout: &str, rules: &[HealthRule]) -> Survey {
+    let mut survey = Survey::default();
+    let mut claimed = HashSet::new();
+    for entry in entries {
+        let url = entry.clone_url().map(String::from);
+        let label = entry.path.clone().or(url.clone()).unwrap_or_default();
+        if let Some(problem) = &entry.problem {
+            survey.results.push(failed(label, url, AppError::Unsupported(problem.clone())));
+            continue;
+        }
+        let dest = match destination(&entry, root, layout) {
+            Ok(dest) => dest,
+            Err(e) => {
+                survey.results.push(failed(label, url, e));
+                continue;
+            }
+        };
+        if !claimed.insert(canonical(&dest)) {
+            survey.results.push(ManifestEntryResult {
+                path: dest.to_string_lossy().to_string(),
+                url,
+                outcome: ManifestOutcome::Duplicate,
+            });
+            continue;
+        }
+
+        if discovery::classify(&dest).is_some() {
+            let info = git::status::get_repo_info(&dest, rules);
+            let reasons = mismatches(&entry, &info);
+            survey.results.push(ManifestEntryResult {
+                path: info.path.clone(),
+                url,
+                outcome: if reasons.is_empty() {
+                    ManifestOutcome::Present
+                } else {
+                    ManifestOutcome::Mismatched { reasons }
+                },
+            });
+            survey.found.push((info, entry.tags));
+            continue;
+        }
+
+        match dest.read_dir().map(|mut dir| dir.next().is_some()) {
+            Ok(true) => {
+                let e = AppError::Conflict(format!("{} exists but is not a git repository", dest.display()));
+                survey.results.push(failed(dest.to_string_lossy().to_string(), url, e));
+            }
+            _ => survey.missing.push((dest, entry)),
+        }
+    }
+    survey
+}
+
+/// Where `entry` belongs: its path under `root`, or for a bare URL, where
+/// the clone layout puts it. No path may lead out of the root, absolute ones
+/// included, so a manifest from elsewhere can't clone into arbitrary places.
+pub fn destination(entry: &ManifestRepo, root: &Path, layout: &str) -> Result<PathBuf, AppError> {
+    let Some(path) = &entry.path else {
+        let url = entry
+            .clone_url()
+            .ok_or_else(|| AppError::General("Manifest entry has neither a path nor a remote".to_string()))?;
+        return git::clone::destination(layout, root, url);
+    };
+    let path = Path::new(path);
+    if path.is_absolute() {
+        let climbs = path.components().any(|c| matches!(c, Component::ParentDir | Component::CurDir));
+        if climbs || !path.starts_with(root) {
+            return Err(AppError::General(format!(
+                "Manifest path '{}' is outside the scan root {}",
+                path.display(),
+                root.display()
+            )));
+        }
+        return Ok(path.to_path_buf());
+    }
+    if path.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(AppError::General(format!(
+            "Manifest path '{}' leaves the scan root",
+            path.display()
+        )));
+    }
+    Ok(root.join(path))
+}
+
+/// `dest` with its longest existing prefix resolved, so two spellings of one
+/// directory (through a symlink, or with `.` components) compare equal even
+/// before anything has been cloned there.
+fn canonical(dest: &Path) -> PathBuf {
+    for ancestor in dest.ancestors() {
+        if let Ok(real) = ancestor.canonicalize() {
+            let rest = dest.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            return real.join(rest);
+        }
+    }
+    dest.to_path_buf()
+}
+
+/// How the repo described by `info` differs from `entry`; empty when it
+/// matches. Remotes are matched by name, except that the remote the entry
+/// clones from may be called `origin` locally.
+fn mismatches(entry: &ManifestRepo, info: &RepoInfo) -> Vec<String> {
+    let mut reasons = Vec::new();
+    let actual = git::detail::get_remotes(Path::new(&info.path)).unwrap_or_default();
+    let clone_remote = entry.clone_remote().map(|r| r.name.as_str());
+
+    for remote in &entry.remotes {
+        let local = actual.iter().find(|r| r.name == remote.name).or_else(|| {
+            (clone_remote == Some(remote.name.as_str()))
+                .then(|| actual.iter().find(|r| r.name == "origin"))
+                .flatten()
+        });
+        match local {
+            None => reasons.push(format!("No '{}' remote ({})", remote.name, remote.url)),
+            Some(local) if !same_remote(&local.url, &remote.url) => reasons.push(format!(
+                "'{}' points at {}, not {}",
+                local.name, local.url, remote.url
+            )),
+            Some(_) => {}
+        }
+    }
+
+    if let (Some(expected), Some(tracking)) = (&entry.default_branch, &info.default_branch) {
+        let actual = branch_name(tracking);
+        if &actual != expected {
+            reasons.push(format!("Default branch is {}, not {}", actual, expected));
+        }
+    }
+    reasons
+}
+
+/// Whether two remote URLs name the same repository, treating the scp-like
+/// and URL forms and a trailing `.git` as equivalent.
+fn same_remote(a: &str, b: &str) -> bool {
+    match (RemoteUrl::parse(a), RemoteUrl::parse(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim_end_matches('/') == b.trim_end_matches('/'),
+    }
+}
+
+/// Clone `entry` into `dest` from its clone remote, then add the rest of
+/// its remotes.
+pub fn clone_entry(entry: &ManifestRepo, dest: &Path, ctx: &OpContext) -> Result<(), AppError> {
+    let source = entry
+        .clone_remote()
+        .ok_or_else(|| AppError::General("Manifest entry has no remote to clone from".to_string()))?;
+    git::clone::clone_repo(&source.url, dest, ctx)?;
+    for remote in entry
+        .remotes
+        .iter()
+        .filter(|r| r.name != "origin" && r.name != source.name)
+    {
+        git::detail::add_remote(dest, &remote.name, &remote.url)?;
+    }
+    Ok(())
+}
+
+pub fn failed(path: String, url: Option<String>, e: AppError) -> ManifestEntryResult {
+    ManifestEntryResult {
+        path,
+        url,
+        outcome: ManifestOutcome::Failed {
+            category: e.category(),
+            message: e.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_manifest_round_trips_its_fields() {
+        let (format, repos) = parse(
+            r#"{ "version": 1, "repos": [
+                { "path": "github.com/acme/cli",
+                  "remotes": [{ "name": "upstream", "url": "https://github.com/tools/cli.git" },
+                              { "name": "origin", "url": "git@github.com:acme/cli.git" }],
+                  "default_branch": "main", "tags": ["work"] }
+            ] }"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(format, ManifestFormat::Native);
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].path.as_deref(), Some("github.com/acme/cli"));
+        assert_eq!(repos[0].clone_url(), Some("git@github.com:acme/cli.git"));
+        assert_eq!(repos[0].default_branch.as_deref(), Some("main"));
+        assert_eq!(repos[0].tags, ["work"]);
+    }
+
+    #[test]
+    fn newer_native_version_is_refused() {
+        assert!(matches!(
+            parse(r#"{ "version": 99, "repos": [] }"#, None),
+            Err(AppError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn repo_xml_projects_use_defaults_and_groups() {
+        let (format, repos) = parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <manifest>
+              <remote name="aosp" fetch="https://android.googlesource.com/" />
+              <default remote="aosp" revision="refs/heads/main" />
+              <project name="platform/build" path="build/make" groups="pdk,tools" />
+              <project name="platform/art" revision="refs/tags/v1" />
+            </manifest>"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(format, ManifestFormat::RepoXml);
+        assert_eq!(repos[0].path.as_deref(), Some("build/make"));
+        assert_eq!(repos[0].clone_url(), Some("https://android.googlesource.com/platform/build"));
+        assert_eq!(repos[0].default_branch.as_deref(), Some("main"));
+        assert_eq!(repos[0].tags, ["pdk", "tools"]);
+        assert_eq!(repos[1].path.as_deref(), Some("platform/art"));
+        assert_eq!(repos[1].default_branch, None);
+    }
+
+    #[test]
+    fn relative_fetch_resolves_against_the_manifest_url() {
+        let xml = r#"<manifest>
+              <remote name="origin" fetch=".." />
+              <project name="tools/cli" remote="origin" />
+            </manifest>"#;
+        let (_, repos) = parse(xml, Some("https://example.com/platform/manifest.git")).unwrap();
+        assert_eq!(repos[0].clone_url(), Some("https://example.com/platform/tools/cli"));
+        assert!(repos[0].problem.is_none());
+
+        let (_, repos) = parse(xml, Some("git@example.com:platform/manifest")).unwrap();
+        assert_eq!(repos[0].clone_url(), Some("git@example.com:platform/tools/cli"));
+    }
+
+    #[test]
+    fn relative_fetch_without_a_manifest_url_fails_only_its_projects() {
+        let (_, repos) = parse(
+            r#"<manifest>
+              <remote name="rel" fetch=".." />
+              <remote name="abs" fetch="https://example.com" />
+              <project name="a" remote="rel" />
+              <project name="b" remote="abs" />
+            </manifest>"#,
+            None,
+        )
+        .unwrap();
+        assert!(repos[0].problem.as_deref().unwrap().contains("'rel'"));
+        assert!(repos[1].problem.is_none());
+    }
+
+    #[test]
+    fn resolve_relative_stops_at_the_host() {
+        assert_eq!(resolve_relative("https://h/a/", "../b"), Some("https://h/b".to_string()));
+        assert_eq!(resolve_relative("https://h/a", ".."), Some("https://h".to_string()));
+        assert_eq!(resolve_relative("https://h/a", "../.."), None);
+        assert_eq!(resolve_relative("git@h:a", ".."), None);
+    }
+
+    #[test]
+    fn repo_xml_include_is_unsupported() {
+        assert!(matches!(
+            parse(r#"<manifest><include name="other.xml" /></manifest>"#, None),
+            Err(AppError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn url_list_skips_comments_and_reads_paths() {
+        let (format, repos) = parse(
+            "# work\nhttps://github.com/acme/cli.git\n\ngit@github.com:acme/api.git services/api\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(format, ManifestFormat::UrlList);
+        assert_eq!(repos.len(), 2);
+        assert_eq!(repos[0].path, None);
+        assert_eq!(repos[1].path.as_deref(), Some("services/api"));
+        assert_eq!(repos[1].clone_url(), Some("git@github.com:acme/api.git"));
+    }
+
+    #[test]
+    fn url_list_reports_the_bad_line() {
+        let err = parse("https://github.com/acme/cli.git\nnot a url\n", None).unwrap_err();
+        assert!(err.to_string().contains("Line 2"));
+    }
+
+    #[test]
+    fn destination_may_not_leave_the_root() {
+        let root = Path::new("/work");
+        let entry = |path: &str| ManifestRepo {
+            path: Some(path.to_string()),
+            remotes: Vec::new(),
+            default_branch: None,
+            tags: Vec::new(),
+            problem: None,
+        };
+        assert_eq!(destination(&entry("a/b"), root, "").unwrap(), root.join("a/b"));
+        assert!(destination(&entry("../b"), root, "").is_err());
+        assert!(destination(&entry("a/../b"), root, "").is_err());
+        assert_eq!(destination(&entry("/work/a"), root, "").unwrap(), root.join("a"));
+        assert!(destination(&entry("/home/me/.config/app"), root, "").is_err());
+        assert!(destination(&entry("/work/../home/me"), root, "").is_err());
+        assert!(destination(&entry("/workshop/a"), root, "").is_err());
+    }
+
+    #[test]
+    fn survey_reports_later_entries_for_one_destination_as_duplicates() {
+        let root = std::env::temp_dir().join("gitatlas-manifest-survey-test");
+        let (_, entries) = parse(
+            "https://github.com/acme/cli.git cli\nhttps://github.com/acme/fork.git cli\n",
+            None,
+        )
+        .unwrap();
+        let survey = survey(entries, &root, "", &[]);
+        assert_eq!(survey.missing.len(), 1);
+        assert_eq!(survey.missing[0].1.clone_url(), Some("https://github.com/acme/cli.git"));
+        assert_eq!(survey.results.len(), 1);
+        assert!(matches!(survey.results[0].outcome, ManifestOutcome::Duplicate));
+        assert_eq!(survey.results[0].url.as_deref(), Some("https://github.com/acme/fork.git"));
+    }
+}
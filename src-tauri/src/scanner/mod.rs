@@ -1,21 +1,187 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
 
-use crate::db::models::RepoInfo;
-use crate::git::{discovery, status};
+use serde::Serialize;
 
-/// Scan multiple root directories for Git repositories,
-/// returning status info for each discovered repo.
-pub fn scan_roots(roots: &[PathBuf]) -> Vec<RepoInfo> {
-    let mut all_repos = Vec::new();
+use crate::db::models::{RepoHealth, RepoInfo};
+use crate::git::progress::CancelToken;
+use crate::git::discovery::{self, ScanRoot};
+use crate::git::{fingerprint, status};
+use crate::health::HealthRule;
 
-    for root in roots {
-        let repo_paths = discovery::discover_repos(root);
-        for path in repo_paths {
-            let info = status::get_repo_info(&path);
-            all_repos.push(info);
+/// How many directories to walk between progress updates.
+const PROGRESS_EVERY_DIRS: usize = 256;
+
+/// The repos found by a scan, and the previously known repos that are gone.
+pub struct ScanOutcome {
+    pub repos: Vec<RepoInfo>,
+    pub removed: Vec<String>,
+    /// False when the scan was cancelled; `repos` then holds only the repos
+    /// finished so far and `removed` is empty.
+    pub complete: bool,
+}
+
+/// A scan update, with running totals.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanEvent {
+    #[serde(flatten)]
+    pub update: ScanUpdate,
+    pub dirs_visited: usize,
+    pub discovered: usize,
+    pub completed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScanUpdate {
+    /// A repo was found; its status is still being read.
+    Discovered { path: String },
+    /// A repo's status is ready.
+    Completed { repo: Box<RepoInfo> },
+    /// Still walking; sent every few hundred directories.
+    Walking,
+}
+
+#[derive(Default)]
+struct Counters {
+    dirs_visited: AtomicUsize,
+    discovered: AtomicUsize,
+    completed: AtomicUsize,
+}
+
+/// Scan multiple root directories for Git repositories, returning status
+/// info for each discovered repo.
+///
+/// Discovery runs on the calling thread while a pool of workers reads the
+/// status of each repo as soon as it is found, reporting both through
+/// `on_event`. Repos in `known` whose metadata fingerprint is unchanged keep
+/// their stored status (with health rules re-applied) instead of being read
+/// again; `full` recomputes every repo. Known repos that weren't found are
+/// returned in `removed`. A repo reached through several roots or symlinks
+/// is scanned once, under the first path it was found at. Cancelling
+/// `cancel` stops the walk and the workers.
+pub fn scan_roots(
+    roots: &[ScanRoot],
+    rules: &[HealthRule],
+    known: Vec<RepoInfo>,
+    full: bool,
+    cancel: &CancelToken,
+    on_event: &(dyn Fn(ScanEvent) + Sync),
+) -> ScanOutcome {
+    let known: Mutex<HashMap<String, RepoInfo>> =
+        Mutex::new(known.into_iter().map(|repo| (repo.path.clone(), repo)).collect());
+    let counters = Counters::default();
+    let results = Mutex::new(Vec::new());
+    let now = chrono::Utc::now().to_rfc3339();
+    let workers = thread::available_parallelism().map_or(4, |n| n.get());
+
+    let emit = |update: ScanUpdate| {
+        on_event(ScanEvent {
+            update,
+            dirs_visited: counters.dirs_visited.load(Ordering::Relaxed),
+            discovered: counters.discovered.load(Ordering::Relaxed),
+            completed: counters.completed.load(Ordering::Relaxed),
+        })
+    };
+
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+    let rx = Mutex::new(rx);
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                // The lock is released as soon as a path is taken
+                let next = rx.lock().unwrap().recv();
+                let Ok(path) = next else {
+                    break;
+                };
+                if cancel.is_cancelled() {
+                    break;
+                }
+                let cached = known.lock().unwrap().remove(&path.to_string_lossy().to_string());
+                let info = read_repo(&path, cached, rules, full, &now);
+                counters.completed.fetch_add(1, Ordering::Relaxed);
+                emit(ScanUpdate::Completed {
+                    repo: Box::new(info.clone()),
+                });
+                results.lock().unwrap().push(info);
+            });
         }
-    }
 
+        let mut seen = HashSet::new();
+        for root in roots {
+            discovery::walk_repos(
+                root,
+                || {
+                    let visited = counters.dirs_visited.fetch_add(1, Ordering::Relaxed) + 1;
+                    if visited % PROGRESS_EVERY_DIRS == 0 {
+                        emit(ScanUpdate::Walking);
+                    }
+                    !cancel.is_cancelled()
+                },
+                |path| {
+                    let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                    if seen.insert(canonical) {
+                        counters.discovered.fetch_add(1, Ordering::Relaxed);
+                        emit(ScanUpdate::Discovered {
+                            path: path.to_string_lossy().to_string(),
+                        });
+                        let _ = tx.send(path);
+                    }
+                },
+            );
+            if cancel.is_cancelled() {
+                break;
+            }
+        }
+        // Closing the channel lets the workers finish once it is drained
+        drop(tx);
+    });
+
+    let mut all_repos = results.into_inner().unwrap();
     all_repos.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    all_repos
+    let complete = !cancel.is_cancelled();
+    ScanOutcome {
+        repos: all_repos,
+        removed: if complete {
+            known.into_inner().unwrap().into_keys().collect()
+        } else {
+            Vec::new()
+        },
+        complete,
+    }
+}
+
+/// Status for one discovered repo: the stored status if its fingerprint is
+/// unchanged, otherwise read afresh.
+fn read_repo(
+    path: &Path,
+    cached: Option<RepoInfo>,
+    rules: &[HealthRule],
+    full: bool,
+    now: &str,
+) -> RepoInfo {
+    let measured = cached.as_ref().map_or(0, |cached| cached.ignored_large_files);
+    let cached = cached.filter(|cached| {
+        // Errors are always re-read; their cause may not show in git metadata
+        !full
+            && cached.health != RepoHealth::Error
+            && cached.fingerprint.is_some()
+            && cached.fingerprint == fingerprint::compute(path)
+    });
+    match cached {
+        Some(mut info) => {
+            status::reevaluate(&mut info, rules);
+            info.last_checked = now.to_string();
+            info
+        }
+        None => {
+            let mut info = status::get_repo_info(path, rules);
+            status::keep_ignored_large(&mut info, measured, rules);
+            info
+        }
+    }
 }
@@ -0,0 +1,231 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use git2::{Repository, StatusOptions};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cache;
+use crate::error::AppError;
+use crate::git::status;
+use crate::AppState;
+
+/// Event emitted with a repo's fresh `RepoInfo` after the watcher refreshes it.
+pub const REPO_UPDATED_EVENT: &str = "repo-updated";
+
+/// Changes closer together than this are handled as one batch, so a commit
+/// or checkout touching many files refreshes each repo once.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Watches repos' working trees and `.git` directories, refreshing a repo's
+/// status (in the database and on the dashboard) whenever it changes.
+pub struct RepoWatcher {
+    debouncer: Debouncer<RecommendedWatcher>,
+    repos: Arc<Mutex<HashSet<PathBuf>>>,
+    /// The paths watched for each repo, to unwatch when it goes away.
+    watches: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl RepoWatcher {
+    pub fn start(app: AppHandle) -> Result<Self, AppError> {
+        let repos = Arc::new(Mutex::new(HashSet::new()));
+        let watched = repos.clone();
+        let debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+            let Ok(events) = result else {
+                return;
+            };
+            let changed = {
+                let watched = watched.lock().unwrap();
+                affected_repos(&watched, events.iter().map(|e| e.path.as_path()))
+            };
+            for path in changed {
+                refresh(&app, &path);
+            }
+        })
+        .map_err(|e| AppError::General(format!("Cannot start file watcher: {}", e)))?;
+
+        Ok(Self {
+            debouncer,
+            repos,
+            watches: HashMap::new(),
+        })
+    }
+
+    /// Watch exactly `paths`: start watching new repos and stop watching
+    /// ones no longer listed. Returns how many repos are being watched;
+    /// fewer than requested usually means the OS watch limit was reached.
+    pub fn sync(&mut self, paths: &[PathBuf]) -> usize {
+        let wanted: HashSet<PathBuf> = paths.iter().cloned().collect();
+        let watcher = self.debouncer.watcher();
+
+        self.watches.retain(|repo, watched| {
+            let keep = wanted.contains(repo);
+            if !keep {
+                for path in watched.iter() {
+                    let _ = watcher.unwatch(path);
+                }
+            }
+            keep
+        });
+        for new in wanted {
+            if let Entry::Vacant(slot) = self.watches.entry(new) {
+                if let Some(watched) = watch_repo(watcher, slot.key()) {
+                    slot.insert(watched);
+                }
+            }
+        }
+
+        *self.repos.lock().unwrap() = self.watches.keys().cloned().collect();
+        self.watches.len()
+    }
+
+    /// Start watching one more repo, e.g. one just cloned. Returns false if
+    /// it couldn't be watched.
+    pub fn add(&mut self, path: PathBuf) -> bool {
+        if self.watches.contains_key(&path) {
+            return true;
+        }
+        let Some(watched) = watch_repo(self.debouncer.watcher(), &path) else {
+            return false;
+        };
+        self.watches.insert(path.clone(), watched);
+        self.repos.lock().unwrap().insert(path);
+        true
+    }
+}
+
+/// Watch the repo at `path`: its `.git` directory, and its working tree
+/// except ignored directories such as `target` or `node_modules`, which can
+/// hold more files than the OS lets one process watch. A directory with
+/// nothing ignored below it gets one recursive watch; a directory above an
+/// ignored one is watched on its own and its other subdirectories are
+/// descended into; a subdirectory created there later is only seen once
+/// live updates restart. Returns the watched paths, or `None` if nothing
+/// could be.
+fn watch_repo(watcher: &mut dyn Watcher, path: &Path) -> Option<Vec<PathBuf>> {
+    let ignored = Repository::open(path)
+        .map(|repo| ignored_dirs(&repo))
+        .unwrap_or_default();
+    let mut watched = Vec::new();
+    if ignored.is_empty() {
+        // Nothing to leave out (or a bare repo): one watch covers it all
+        watcher.watch(path, RecursiveMode::Recursive).ok()?;
+        return Some(vec![path.to_path_buf()]);
+    }
+
+    let git_dir = path.join(".git");
+    if git_dir.is_dir() && watcher.watch(&git_dir, RecursiveMode::Recursive).is_ok() {
+        watched.push(git_dir);
+    }
+    watch_tree(watcher, path, Path::new(""), &ignored, &mut watched);
+    (!watched.is_empty()).then_some(watched)
+}
+
+/// Watch `root/rel`, which isn't ignored itself, leaving out the `ignored`
+/// directories (relative to `root`) below it. Symlinks and nested `.git`
+/// directories aren't descended into.
+fn watch_tree(
+    watcher: &mut dyn Watcher,
+    root: &Path,
+    rel: &Path,
+    ignored: &[PathBuf],
+    watched: &mut Vec<PathBuf>,
+) {
+    let dir = root.join(rel);
+    if !ignored.iter().any(|i| i.starts_with(rel)) {
+        if watcher.watch(&dir, RecursiveMode::Recursive).is_ok() {
+            watched.push(dir);
+        }
+        return;
+    }
+
+    if watcher.watch(&dir, RecursiveMode::NonRecursive).is_ok() {
+        watched.push(dir.clone());
+    }
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) || entry.file_name() == ".git" {
+            continue;
+        }
+        let child = rel.join(entry.file_name());
+        if !ignored.contains(&child) {
+            watch_tree(watcher, root, &child, ignored, watched);
+        }
+    }
+}
+
+/// The repo's ignored directories, relative to its working tree, as one
+/// status pass reports them (without walking into them). Untracked
+/// directories are walked, since a project not yet committed can have its
+/// own `node_modules`.
+fn ignored_dirs(repo: &Repository) -> Vec<PathBuf> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(true)
+        .recurse_ignored_dirs(false);
+    let Ok(statuses) = repo.statuses(Some(&mut opts)) else {
+        return Vec::new();
+    };
+    statuses
+        .iter()
+        .filter(|entry| entry.status().is_ignored())
+        .filter_map(|entry| entry.path()?.strip_suffix('/').map(PathBuf::from))
+        .collect()
+}
+
+/// The watched repos that `changed` paths belong to, skipping changes that
+/// can't affect status: new objects (always followed by a ref or index
+/// update) and files the repo ignores, such as build output.
+fn affected_repos<'a>(
+    watched: &HashSet<PathBuf>,
+    changed: impl Iterator<Item = &'a Path>,
+) -> Vec<PathBuf> {
+    let mut by_repo: HashMap<&PathBuf, Vec<&Path>> = HashMap::new();
+    for path in changed {
+        // The innermost repo wins for nested repos and submodules
+        let Some(repo) = watched
+            .iter()
+            .filter(|repo| path.starts_with(repo))
+            .max_by_key(|repo| repo.components().count())
+        else {
+            continue;
+        };
+        if let Ok(rel) = path.strip_prefix(repo) {
+            by_repo.entry(repo).or_default().push(rel);
+        }
+    }
+
+    by_repo
+        .into_iter()
+        .filter(|(repo, paths)| {
+            let git = Repository::open(repo).ok();
+            paths.iter().any(|rel| {
+                if rel.starts_with(".git") {
+                    !rel.starts_with(".git/objects")
+                } else {
+                    !git.as_ref()
+                        .is_some_and(|git| git.is_path_ignored(rel).unwrap_or(false))
+                }
+            })
+        })
+        .map(|(repo, _)| repo.clone())
+        .collect()
+}
+
+fn refresh(app: &AppHandle, path: &Path) {
+    let rules = cache::load_config().health_rules;
+    let mut info = status::get_repo_info(path, &rules);
+    let state = app.state::<AppState>();
+    let measured = state.db.get_ignored_large_files(&info.path).unwrap_or(0);
+    status::keep_ignored_large(&mut info, measured, &rules);
+    if state.db.upsert_repo(&info).is_ok() {
+        let _ = app.emit(REPO_UPDATED_EVENT, &info);
+    }
+}
@@ -1,14 +1,18 @@
 interface BulkActionsProps {
   onFetchAll: () => void;
   onPullAll: () => void;
+  onCancel: () => void;
   disabled: boolean;
+  running: boolean;
   repoCount: number;
 }
 
 export default function BulkActions({
   onFetchAll,
   onPullAll,
+  onCancel,
   disabled,
+  running,
   repoCount,
 }: BulkActionsProps) {
   if (repoCount === 0) return null;
@@ -29,6 +33,14 @@ export default function BulkActions({
       >
         Pull All
       </button>
+      {running && (
+        <button
+          onClick={onCancel}
+          className="rounded-md border border-red-700 px-3 py-1.5 text-sm font-medium text-red-300 transition hover:bg-red-900/40"
+        >
+          Cancel
+        </button>
+      )}
     </div>
   );
 }
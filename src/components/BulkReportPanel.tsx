@@ -0,0 +1,71 @@
+import type { BulkReport, ErrorCategory } from "../types";
+
+const CATEGORY_LABELS: Record<ErrorCategory, string> = {
+  auth: "Authentication",
+  network: "Network",
+  conflict: "Conflict",
+  no_upstream: "No upstream",
+  no_remote: "No remote",
+  detached_head: "Detached HEAD",
+  not_found: "Not found",
+  locked: "Locked",
+  cancelled: "Cancelled",
+  database: "Database",
+  io: "I/O",
+  other: "Other",
+};
+
+interface BulkReportPanelProps {
+  report: BulkReport;
+  onDismiss: () => void;
+}
+
+export default function BulkReportPanel({ report, onDismiss }: BulkReportPanelProps) {
+  const { summary } = report;
+  const problems = report.results.filter((r) => r.outcome.status !== "success");
+  const verb = report.operation === "fetch" ? "Fetch" : "Pull";
+
+  return (
+    <div
+      className={`mb-4 rounded-md border px-4 py-3 text-sm ${
+        summary.failed > 0
+          ? "border-red-800 bg-red-900/20 text-red-200"
+          : "border-slate-700 bg-slate-800/50 text-slate-300"
+      }`}
+    >
+      <div className="flex items-center justify-between gap-3">
+        <p>
+          {verb} finished: {summary.succeeded} succeeded, {summary.skipped} skipped,{" "}
+          {summary.failed} failed of {summary.total}
+          {Object.entries(summary.failed_by_category).map(([category, count]) => (
+            <span key={category} className="ml-2 rounded bg-red-900/60 px-1.5 py-0.5 text-xs text-red-300">
+              {CATEGORY_LABELS[category as ErrorCategory]} {count}
+            </span>
+          ))}
+        </p>
+        <button onClick={onDismiss} className="text-xs text-slate-400 hover:text-slate-200 transition">
+          Dismiss
+        </button>
+      </div>
+
+      {problems.length > 0 && (
+        <ul className="mt-2 max-h-48 space-y-1 overflow-y-auto text-xs">
+          {problems.map((r) => (
+            <li key={r.path} className="flex gap-2">
+              <span className="shrink-0 font-medium text-slate-200">{r.name}</span>
+              {r.outcome.status === "failed" ? (
+                <span className="truncate text-red-300" title={r.outcome.message}>
+                  [{CATEGORY_LABELS[r.outcome.category]}] {r.outcome.message}
+                </span>
+              ) : r.outcome.status === "skipped" ? (
+                <span className="truncate text-slate-400" title={r.outcome.reason}>
+                  skipped: {r.outcome.reason}
+                </span>
+              ) : null}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,124 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { GitProgressEvent, RepoInfo, ScanRoot } from "../types";
+import { errorMessage } from "../utils/errors";
+
+interface ClonePanelProps {
+  scanRoots: ScanRoot[];
+  progress: Record<string, GitProgressEvent>;
+  onClone: (url: string, root: string | null) => Promise<RepoInfo>;
+  onCancel: (url: string) => Promise<void>;
+  onClose: () => void;
+}
+
+function describeTransfer(p: GitProgressEvent | undefined): string | null {
+  if (!p) return null;
+  if (p.stage === "transfer") {
+    return p.received_objects < p.total_objects
+      ? `Receiving ${p.received_objects}/${p.total_objects} objects`
+      : `Resolving deltas ${p.indexed_deltas}/${p.total_deltas}`;
+  }
+  return p.stage === "remote" ? p.message : null;
+}
+
+export default function ClonePanel({ scanRoots, progress, onClone, onCancel, onClose }: ClonePanelProps) {
+  const roots = scanRoots.filter((r) => r.enabled);
+  const [url, setUrl] = useState("");
+  const [root, setRoot] = useState<string | null>(roots[0]?.path ?? null);
+  const [destination, setDestination] = useState<string | null>(null);
+  const [cloning, setCloning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [done, setDone] = useState<string | null>(null);
+
+  // Preview where the layout template puts this URL
+  useEffect(() => {
+    const trimmed = url.trim();
+    if (!trimmed) {
+      setDestination(null);
+      return;
+    }
+    let stale = false;
+    invoke<string>("get_clone_destination", { url: trimmed, root })
+      .then((dest) => !stale && setDestination(dest))
+      .catch(() => !stale && setDestination(null));
+    return () => {
+      stale = true;
+    };
+  }, [url, root]);
+
+  const handleClone = async () => {
+    const trimmed = url.trim();
+    if (!trimmed) return;
+    setCloning(true);
+    setError(null);
+    setDone(null);
+    try {
+      const repo = await onClone(trimmed, root);
+      setDone(repo.path);
+      setUrl("");
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setCloning(false);
+    }
+  };
+
+  const status = cloning && destination ? describeTransfer(progress[destination]) : null;
+
+  return (
+    <div className="mb-6 rounded-lg border border-slate-700 bg-slate-800/50 p-4 text-sm">
+      <div className="flex items-center gap-2">
+        <input
+          autoFocus
+          value={url}
+          onChange={(e) => setUrl(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") handleClone();
+            if (e.key === "Escape") onClose();
+          }}
+          placeholder="git@github.com:owner/repo.git"
+          disabled={cloning}
+          className="flex-1 min-w-0 rounded bg-slate-800 border border-slate-600 px-2 py-1 font-mono text-slate-200 placeholder-slate-500 focus:border-indigo-500 focus:outline-none"
+        />
+        {roots.length > 1 && (
+          <select
+            value={root ?? ""}
+            onChange={(e) => setRoot(e.target.value)}
+            disabled={cloning}
+            className="rounded bg-slate-800 border border-slate-600 px-2 py-1 text-slate-300"
+          >
+            {roots.map((r) => (
+              <option key={r.path} value={r.path}>
+                {r.path}
+              </option>
+            ))}
+          </select>
+        )}
+        {cloning ? (
+          <button
+            onClick={() => onCancel(url.trim()).catch(() => {})}
+            className="rounded-md border border-red-700 px-3 py-1 font-medium text-red-300 transition hover:bg-red-900/40"
+          >
+            Cancel
+          </button>
+        ) : (
+          <button
+            onClick={handleClone}
+            disabled={!destination}
+            className="rounded-md bg-indigo-600 px-3 py-1 font-medium text-white transition hover:bg-indigo-500 disabled:opacity-50"
+          >
+            Clone
+          </button>
+        )}
+        <button onClick={onClose} className="px-1 text-slate-500 hover:text-slate-300" title="Close">
+          ✕
+        </button>
+      </div>
+      <p className="mt-2 truncate text-xs text-slate-500 font-mono">
+        {status ?? (destination ? `→ ${destination}` : "Enter a remote URL")}
+      </p>
+      {done && <p className="mt-1 text-xs text-green-400">Cloned into {done}</p>}
+      {error && <p className="mt-1 text-xs text-red-400">{error}</p>}
+    </div>
+  );
+}
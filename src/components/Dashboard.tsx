@@ -1,47 +1,120 @@
 import { useState, useEffect, useMemo, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useRepos } from "../hooks/useRepos";
-import type { RepoHealth, RepoInfo } from "../types";
+import type { RepoFlag, RepoHealth, RepoInfo, RepoQuery, ScanRoot } from "../types";
 import FilterBar from "./FilterBar";
 import RepoList from "./RepoList";
 import BulkActions from "./BulkActions";
+import BulkReportPanel from "./BulkReportPanel";
+import ClonePanel from "./ClonePanel";
+import ManifestPanel from "./ManifestPanel";
+import UnpushedPanel from "./UnpushedPanel";
 import GitHubLink from "./GitHubLink";
 import RepoDetail from "./detail/RepoDetail";
 
+// Mirrors `ScanRoot::new` in git/discovery.rs
+const NEW_ROOT: ScanRoot = {
+  path: "",
+  max_depth: 5,
+  include: [],
+  exclude: ["node_modules", "target", "vendor"],
+  follow_symlinks: false,
+  enabled: true,
+  nested: "skip",
+};
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+const daysAgo = (days: number) => new Date(Date.now() - days * DAY_MS).toISOString();
+
+// Orderings and recency filters applied by the database
+const SORT_OPTIONS: { value: string; label: string; query: RepoQuery }[] = [
+  { value: "name", label: "Name", query: { sort: "name" } },
+  { value: "activity", label: "Recently worked on", query: { sort: "last_activity", descending: true } },
+  { value: "commit", label: "Latest commit", query: { sort: "last_commit", descending: true } },
+  { value: "fetch", label: "Least recently fetched", query: { sort: "last_fetch" } },
+];
+
+const RECENCY_OPTIONS: { value: string; label: string; query: () => RepoQuery }[] = [
+  { value: "any", label: "Any time", query: () => ({}) },
+  { value: "active", label: "Active in last 7 days", query: () => ({ active_since: daysAgo(7) }) },
+  { value: "unfetched", label: "Not fetched in 14 days", query: () => ({ fetched_before: daysAgo(14) }) },
+  { value: "idle", label: "Idle for 90 days", query: () => ({ inactive_since: daysAgo(90) }) },
+];
+
 export default function Dashboard() {
   const {
-    repos, loading, error,
-    scanRepos, fetchAll, pullAll,
-    fetchRepo, pullRebaseRepo, pushRepo,
+    repos, loading, error, bulkReport, clearBulkReport, progress, watching, toggleWatching,
+    scanProgress, scanRepos, cancelScan, queryRepos, fetchAll, pullAll, cancelBulk,
+    fetchRepo, pullRebaseRepo, pushRepo, cancelRepoOperation, cloneRepo, cancelClone,
+    exportManifest, syncManifest, cancelManifestSync,
+    getUnpushedReport, exportUnpushedReport, resolveInProgress, measureIgnoredLargeFiles,
   } = useRepos();
-  const [scanRoots, setScanRoots] = useState<string[]>([]);
+  const [showClone, setShowClone] = useState(false);
+  const [showManifest, setShowManifest] = useState(false);
+  const [showUnpushed, setShowUnpushed] = useState(false);
+  const [bulkRunning, setBulkRunning] = useState(false);
+  const [scanRoots, setScanRoots] = useState<ScanRoot[]>([]);
   const [editingRoot, setEditingRoot] = useState<string | null>(null);
   const [activeFilters, setActiveFilters] = useState<Set<RepoHealth>>(new Set());
+  const [activeFlags, setActiveFlags] = useState<Set<RepoFlag>>(new Set());
   const [search, setSearch] = useState("");
+  const [sort, setSort] = useState("name");
+  const [recency, setRecency] = useState("any");
   const [selectedRepo, setSelectedRepo] = useState<RepoInfo | null>(null);
+  const [configProblems, setConfigProblems] = useState<string[]>([]);
 
   useEffect(() => {
-    invoke<string[]>("get_scan_roots").then(setScanRoots).catch(() => {});
+    invoke<ScanRoot[]>("get_scan_roots").then(setScanRoots).catch(() => {});
+    invoke<string[]>("get_config_problems").then(setConfigProblems).catch(() => {});
   }, []);
 
-  const handleScan = () => {
+  // Opening a repo's detail view is when its large ignored files get counted
+  const selectedPath = selectedRepo?.path;
+  useEffect(() => {
+    if (selectedPath) {
+      measureIgnoredLargeFiles(selectedPath).catch(() => {});
+    }
+  }, [selectedPath, measureIgnoredLargeFiles]);
+
+  // Unchanged repos keep their stored status; a full scan re-reads every repo
+  const handleScan = (full: boolean) => {
     if (scanRoots.length > 0) {
-      scanRepos(scanRoots);
+      scanRepos(full);
     }
   };
 
+  // Only the first root's path is editable here; other roots and their
+  // depth/glob/symlink settings live in config.json
   const saveScanRoot = useCallback(
     async (value: string) => {
       const trimmed = value.trim();
       if (!trimmed) return;
-      const roots = [trimmed];
+      const [first, ...rest] = scanRoots;
+      const roots: ScanRoot[] = [{ ...(first ?? NEW_ROOT), path: trimmed }, ...rest];
       setScanRoots(roots);
       setEditingRoot(null);
       await invoke("set_scan_roots", { roots }).catch(() => {});
     },
-    [],
+    [scanRoots],
   );
 
+  const runBulk = async (action: () => Promise<void>) => {
+    setBulkRunning(true);
+    try {
+      await action();
+    } finally {
+      setBulkRunning(false);
+    }
+  };
+
+  const applyQuery = (nextSort: string, nextRecency: string) => {
+    setSort(nextSort);
+    setRecency(nextRecency);
+    const sortQuery = SORT_OPTIONS.find((o) => o.value === nextSort)?.query ?? {};
+    const recencyQuery = RECENCY_OPTIONS.find((o) => o.value === nextRecency)?.query() ?? {};
+    queryRepos({ ...sortQuery, ...recencyQuery });
+  };
+
   const toggleFilter = (health: RepoHealth) => {
     setActiveFilters((prev) => {
       const next = new Set(prev);
@@ -54,6 +127,18 @@ export default function Dashboard() {
     });
   };
 
+  const toggleFlag = (flag: RepoFlag) => {
+    setActiveFlags((prev) => {
+      const next = new Set(prev);
+      if (next.has(flag)) {
+        next.delete(flag);
+      } else {
+        next.add(flag);
+      }
+      return next;
+    });
+  };
+
   const filteredRepos = useMemo(() => {
     let result = repos;
 
@@ -61,6 +146,10 @@ export default function Dashboard() {
       result = result.filter((r) => activeFilters.has(r.health));
     }
 
+    if (activeFlags.size > 0) {
+      result = result.filter((r) => [...activeFlags].some((flag) => r[flag]));
+    }
+
     const query = search.trim().toLowerCase();
     if (query) {
       result = result.filter(
@@ -72,7 +161,7 @@ export default function Dashboard() {
     }
 
     return result;
-  }, [repos, activeFilters, search]);
+  }, [repos, activeFilters, activeFlags, search]);
 
   // Show detail view as fullscreen overlay
   if (selectedRepo) {
@@ -98,18 +187,64 @@ export default function Dashboard() {
         </div>
         <div className="flex items-center gap-3">
           <BulkActions
-            onFetchAll={fetchAll}
-            onPullAll={pullAll}
+            onFetchAll={() => runBulk(fetchAll)}
+            onPullAll={() => runBulk(pullAll)}
+            onCancel={() => cancelBulk().catch(() => {})}
             disabled={loading}
+            running={bulkRunning}
             repoCount={repos.length}
           />
           <button
-            onClick={handleScan}
+            onClick={() => setShowClone(!showClone)}
+            disabled={scanRoots.length === 0}
+            className="rounded-md border border-slate-600 px-3 py-1.5 text-sm text-slate-300 transition hover:text-white disabled:opacity-50"
+            title="Clone a repository into a scan root"
+          >
+            Clone
+          </button>
+          <button
+            onClick={() => setShowManifest(!showManifest)}
+            disabled={scanRoots.length === 0}
+            className="rounded-md border border-slate-600 px-3 py-1.5 text-sm text-slate-300 transition hover:text-white disabled:opacity-50"
+            title="Export the repo list to a manifest, or sync from one"
+          >
+            Manifest
+          </button>
+          <button
+            onClick={() => setShowUnpushed(!showUnpushed)}
+            disabled={repos.length === 0}
+            className="rounded-md border border-slate-600 px-3 py-1.5 text-sm text-slate-300 transition hover:text-white disabled:opacity-50"
+            title="List unpushed commits, stashes and uncommitted files across all repos"
+          >
+            Unpushed
+          </button>
+          <button
+            onClick={toggleWatching}
+            className={`rounded-md border px-3 py-1.5 text-sm transition ${
+              watching
+                ? "border-green-700 bg-green-900/30 text-green-300 hover:bg-green-900/50"
+                : "border-slate-600 text-slate-400 hover:text-slate-200"
+            }`}
+            title={watching ? "Stop watching repos for changes" : "Update repo status as files change"}
+          >
+            {watching ? "● Live" : "○ Live"}
+          </button>
+          <button
+            onClick={(e) => handleScan(e.shiftKey)}
             disabled={loading || scanRoots.length === 0}
+            title="Shift-click to re-read every repo, including unstaged working tree changes"
             className="rounded-md bg-indigo-600 px-4 py-1.5 text-sm font-medium text-white transition hover:bg-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed"
           >
             {loading ? "Scanning..." : "Scan for Repos"}
           </button>
+          {scanProgress && (
+            <button
+              onClick={() => cancelScan().catch(() => {})}
+              className="rounded-md border border-red-700 px-3 py-1.5 text-sm font-medium text-red-300 transition hover:bg-red-900/40"
+            >
+              Cancel
+            </button>
+          )}
         </div>
       </header>
 
@@ -137,44 +272,123 @@ export default function Dashboard() {
           </form>
         ) : (
           <button
-            onClick={() => setEditingRoot(scanRoots[0] ?? "")}
+            onClick={() => setEditingRoot(scanRoots[0]?.path ?? "")}
             className="truncate font-mono text-slate-300 hover:text-white transition cursor-pointer"
             title="Click to change scan root"
           >
-            {scanRoots[0] ?? "Not set"}
+            {scanRoots[0]?.path ?? "Not set"}
+            {scanRoots.length > 1 && ` (+${scanRoots.length - 1} more)`}
           </button>
         )}
       </div>
 
+      {showClone && (
+        <ClonePanel
+          scanRoots={scanRoots}
+          progress={progress}
+          onClone={cloneRepo}
+          onCancel={cancelClone}
+          onClose={() => setShowClone(false)}
+        />
+      )}
+      {showManifest && (
+        <ManifestPanel
+          scanRoots={scanRoots}
+          progress={progress}
+          onExport={exportManifest}
+          onSync={syncManifest}
+          onCancel={cancelManifestSync}
+          onClose={() => setShowManifest(false)}
+        />
+      )}
+      {showUnpushed && (
+        <UnpushedPanel
+          onLoad={getUnpushedReport}
+          onExport={exportUnpushedReport}
+          onClose={() => setShowUnpushed(false)}
+        />
+      )}
+
+      {scanProgress && (
+        <div className="mb-4 text-xs text-slate-400">
+          Scanned {scanProgress.dirs_visited.toLocaleString()} directories ·{" "}
+          {scanProgress.completed} of {scanProgress.discovered} repos read
+        </div>
+      )}
+
+      {configProblems.length > 0 && (
+        <div className="mb-4 rounded-md bg-amber-900/30 border border-amber-800 px-4 py-3 text-sm text-amber-300">
+          <p className="font-medium">Problems in ~/.gitatlas/config.json</p>
+          {configProblems.map((problem) => (
+            <p key={problem} className="font-mono text-xs">
+              {problem}
+            </p>
+          ))}
+        </div>
+      )}
+
       {error && (
         <div className="mb-4 rounded-md bg-red-900/30 border border-red-800 px-4 py-3 text-sm text-red-300">
           {error}
         </div>
       )}
 
+      {bulkReport && (
+        <BulkReportPanel report={bulkReport} onDismiss={clearBulkReport} />
+      )}
+
       {repos.length > 0 && (
         <FilterBar
           repos={repos}
           activeFilters={activeFilters}
           onToggleFilter={toggleFilter}
+          activeFlags={activeFlags}
+          onToggleFlag={toggleFlag}
           search={search}
           onSearchChange={setSearch}
         />
       )}
 
-      <div className="mb-4 text-sm text-slate-500">
-        {repos.length === 0
-          ? "No repositories scanned yet"
-          : filteredRepos.length === repos.length
-            ? `${repos.length} repositories`
-            : `${filteredRepos.length} of ${repos.length} repositories`}
+      <div className="mb-4 flex items-center justify-between gap-3 text-sm text-slate-500">
+        <span>
+          {repos.length === 0
+            ? "No repositories scanned yet"
+            : filteredRepos.length === repos.length
+              ? `${repos.length} repositories`
+              : `${filteredRepos.length} of ${repos.length} repositories`}
+        </span>
+        <div className="flex items-center gap-2">
+          <select
+            value={recency}
+            onChange={(e) => applyQuery(sort, e.target.value)}
+            className="rounded bg-slate-800 border border-slate-600 px-2 py-0.5 text-xs text-slate-300"
+            title="Filter by recency"
+          >
+            {RECENCY_OPTIONS.map(({ value, label }) => (
+              <option key={value} value={value}>{label}</option>
+            ))}
+          </select>
+          <select
+            value={sort}
+            onChange={(e) => applyQuery(e.target.value, recency)}
+            className="rounded bg-slate-800 border border-slate-600 px-2 py-0.5 text-xs text-slate-300"
+            title="Sort by"
+          >
+            {SORT_OPTIONS.map(({ value, label }) => (
+              <option key={value} value={value}>{label}</option>
+            ))}
+          </select>
+        </div>
       </div>
 
       <RepoList
         repos={filteredRepos}
+        progress={progress}
         onFetch={fetchRepo}
         onPullRebase={pullRebaseRepo}
         onPush={pushRepo}
+        onCancel={cancelRepoOperation}
+        onResolve={resolveInProgress}
         onOpen={setSelectedRepo}
       />
     </div>
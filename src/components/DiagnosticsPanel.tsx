@@ -0,0 +1,50 @@
+import type { CheckStatus, RepoInfo } from "../types";
+import { useRepoDiagnostics } from "../hooks/useRepoDiagnostics";
+
+const STATUS_STYLES: Record<CheckStatus, string> = {
+  ok: "text-green-400",
+  warning: "text-yellow-400",
+  failed: "text-red-400",
+};
+
+const STATUS_ICONS: Record<CheckStatus, string> = {
+  ok: "✓",
+  warning: "!",
+  failed: "✗",
+};
+
+export default function DiagnosticsPanel({ repo }: { repo: RepoInfo }) {
+  const { diagnostics, loading, error, run } = useRepoDiagnostics(repo.path);
+
+  return (
+    <div className="flex flex-col gap-2 rounded border border-red-900/50 bg-red-950/30 p-2 text-xs">
+      {repo.error_detail && (
+        <p className="text-red-300 break-words">{repo.error_detail}</p>
+      )}
+
+      {diagnostics && (
+        <ul className="flex flex-col gap-1">
+          {diagnostics.checks.map((check) => (
+            <li key={check.name} className="flex gap-2">
+              <span className={`w-3 shrink-0 font-bold ${STATUS_STYLES[check.status]}`}>
+                {STATUS_ICONS[check.status]}
+              </span>
+              <span className="shrink-0 text-slate-300">{check.name}</span>
+              <span className="min-w-0 break-words text-slate-500">{check.detail}</span>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      {error && <p className="text-red-400">{error}</p>}
+
+      <button
+        onClick={run}
+        disabled={loading}
+        className="self-start rounded bg-slate-700/60 px-2 py-0.5 font-medium text-slate-300 transition hover:bg-slate-600 disabled:opacity-40"
+      >
+        {loading ? "Diagnosing..." : diagnostics ? "Re-run diagnostics" : "Diagnose"}
+      </button>
+    </div>
+  );
+}
@@ -1,16 +1,28 @@
-import type { RepoHealth, RepoInfo } from "../types";
+import type { RepoFlag, RepoHealth, RepoInfo } from "../types";
 
 const HEALTH_OPTIONS: { value: RepoHealth; label: string; dot: string }[] = [
   { value: "clean", label: "Clean", dot: "bg-green-500" },
   { value: "dirty", label: "Changes", dot: "bg-yellow-500" },
   { value: "diverged", label: "Diverged", dot: "bg-red-500" },
+  { value: "behind_trunk", label: "Behind trunk", dot: "bg-amber-600" },
+  { value: "in_progress", label: "In progress", dot: "bg-orange-500" },
   { value: "error", label: "Error", dot: "bg-gray-500" },
 ];
 
+export const FLAG_OPTIONS: { value: RepoFlag; label: string }[] = [
+  { value: "detached", label: "Detached" },
+  { value: "no_origin", label: "No origin" },
+  { value: "no_upstream", label: "No upstream" },
+  { value: "upstream_gone", label: "Upstream gone" },
+  { value: "conflicted", label: "Conflicted" },
+];
+
 interface FilterBarProps {
   repos: RepoInfo[];
   activeFilters: Set<RepoHealth>;
   onToggleFilter: (health: RepoHealth) => void;
+  activeFlags: Set<RepoFlag>;
+  onToggleFlag: (flag: RepoFlag) => void;
   search: string;
   onSearchChange: (value: string) => void;
 }
@@ -19,6 +31,8 @@ export default function FilterBar({
   repos,
   activeFilters,
   onToggleFilter,
+  activeFlags,
+  onToggleFlag,
   search,
   onSearchChange,
 }: FilterBarProps) {
@@ -53,6 +67,28 @@ export default function FilterBar({
         })}
       </div>
 
+      <div className="flex items-center gap-1.5">
+        {FLAG_OPTIONS.map(({ value, label }) => {
+          const count = repos.filter((r) => r[value]).length;
+          if (count === 0 && !activeFlags.has(value)) return null;
+          const active = activeFlags.has(value);
+          return (
+            <button
+              key={value}
+              onClick={() => onToggleFlag(value)}
+              className={`inline-flex items-center gap-1.5 rounded border px-2 py-0.5 text-xs font-medium transition ${
+                active
+                  ? "border-slate-500 bg-slate-700 text-slate-200"
+                  : "border-slate-700 bg-transparent text-slate-500 hover:border-slate-600 hover:text-slate-400"
+              }`}
+            >
+              {label}
+              <span className={active ? "text-slate-400" : "text-slate-600"}>{count}</span>
+            </button>
+          );
+        })}
+      </div>
+
       <input
         type="text"
         value={search}
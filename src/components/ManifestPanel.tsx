@@ -0,0 +1,193 @@
+import { useState } from "react";
+import type { GitProgressEvent, ManifestEntryResult, ManifestSyncReport, ScanRoot } from "../types";
+import { errorMessage } from "../utils/errors";
+
+interface ManifestPanelProps {
+  scanRoots: ScanRoot[];
+  progress: Record<string, GitProgressEvent>;
+  onExport: (file: string) => Promise<number>;
+  onSync: (file: string, root: string | null, cloneMissing: boolean) => Promise<ManifestSyncReport>;
+  onCancel: () => Promise<void>;
+  onClose: () => void;
+}
+
+const FORMAT_LABELS: Record<ManifestSyncReport["format"], string> = {
+  native: "gitatlas manifest",
+  repo_xml: "repo manifest",
+  url_list: "URL list",
+};
+
+const OUTCOME_STYLES: Record<ManifestEntryResult["outcome"]["status"], string> = {
+  present: "text-slate-500",
+  cloned: "text-green-400",
+  missing: "text-amber-400",
+  mismatched: "text-sky-400",
+  duplicate: "text-amber-400",
+  failed: "text-red-400",
+};
+
+function details(entry: ManifestEntryResult): string[] {
+  switch (entry.outcome.status) {
+    case "mismatched":
+      return entry.outcome.reasons;
+    case "failed":
+      return [entry.outcome.message];
+    case "missing":
+      return entry.url ? [entry.url] : [];
+    case "duplicate":
+      return [`Skipped: an earlier entry goes here${entry.url ? ` (${entry.url} not synced)` : ""}`];
+    default:
+      return [];
+  }
+}
+
+export default function ManifestPanel({
+  scanRoots,
+  progress,
+  onExport,
+  onSync,
+  onCancel,
+  onClose,
+}: ManifestPanelProps) {
+  const roots = scanRoots.filter((r) => r.enabled);
+  const [file, setFile] = useState("");
+  const [root, setRoot] = useState<string | null>(roots[0]?.path ?? null);
+  const [cloneMissing, setCloneMissing] = useState(false);
+  const [busy, setBusy] = useState<"export" | "sync" | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [report, setReport] = useState<ManifestSyncReport | null>(null);
+
+  const run = async (kind: "export" | "sync") => {
+    const trimmed = file.trim();
+    if (!trimmed) return;
+    setBusy(kind);
+    setError(null);
+    setMessage(null);
+    try {
+      if (kind === "export") {
+        const count = await onExport(trimmed);
+        setMessage(`Exported ${count} repo${count === 1 ? "" : "s"} to ${trimmed}`);
+      } else {
+        setReport(await onSync(trimmed, root, cloneMissing));
+      }
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const cloning = Object.values(progress).filter((p) => p.operation_id === "manifest-sync").length;
+  const counts = report?.entries.reduce<Record<string, number>>((acc, e) => {
+    acc[e.outcome.status] = (acc[e.outcome.status] ?? 0) + 1;
+    return acc;
+  }, {});
+  const notable = report?.entries.filter((e) => e.outcome.status !== "present") ?? [];
+
+  return (
+    <div className="mb-6 rounded-lg border border-slate-700 bg-slate-800/50 p-4 text-sm">
+      <div className="flex items-center gap-2">
+        <input
+          autoFocus
+          value={file}
+          onChange={(e) => setFile(e.target.value)}
+          onKeyDown={(e) => e.key === "Escape" && onClose()}
+          placeholder="Path to a manifest: workspace.json, default.xml or a list of URLs"
+          disabled={busy !== null}
+          className="flex-1 min-w-0 rounded bg-slate-800 border border-slate-600 px-2 py-1 font-mono text-slate-200 placeholder-slate-500 focus:border-indigo-500 focus:outline-none"
+        />
+        {roots.length > 1 && (
+          <select
+            value={root ?? ""}
+            onChange={(e) => setRoot(e.target.value)}
+            disabled={busy !== null}
+            className="rounded bg-slate-800 border border-slate-600 px-2 py-1 text-slate-300"
+          >
+            {roots.map((r) => (
+              <option key={r.path} value={r.path}>
+                {r.path}
+              </option>
+            ))}
+          </select>
+        )}
+        <label className="flex items-center gap-1 text-xs text-slate-400">
+          <input
+            type="checkbox"
+            checked={cloneMissing}
+            onChange={(e) => setCloneMissing(e.target.checked)}
+            disabled={busy !== null}
+          />
+          clone missing
+        </label>
+        <button
+          onClick={() => run("export")}
+          disabled={busy !== null || !file.trim()}
+          className="rounded-md border border-slate-600 px-3 py-1 text-slate-300 transition hover:text-white disabled:opacity-50"
+        >
+          Export
+        </button>
+        {busy === "sync" && cloneMissing ? (
+          <button
+            onClick={() => onCancel().catch(() => {})}
+            className="rounded-md border border-red-700 px-3 py-1 font-medium text-red-300 transition hover:bg-red-900/40"
+          >
+            Cancel
+          </button>
+        ) : (
+          <button
+            onClick={() => run("sync")}
+            disabled={busy !== null || !file.trim()}
+            className="rounded-md bg-indigo-600 px-3 py-1 font-medium text-white transition hover:bg-indigo-500 disabled:opacity-50"
+          >
+            Sync
+          </button>
+        )}
+        <button onClick={onClose} className="px-1 text-slate-500 hover:text-slate-300" title="Close">
+          ✕
+        </button>
+      </div>
+
+      {busy === "sync" && cloning > 0 && (
+        <p className="mt-2 text-xs text-slate-500">Cloning {cloning} repo{cloning === 1 ? "" : "s"}…</p>
+      )}
+      {message && <p className="mt-2 text-xs text-green-400">{message}</p>}
+      {error && <p className="mt-2 text-xs text-red-400">{error}</p>}
+
+      {report && counts && (
+        <div className="mt-3 text-xs">
+          <p className="text-slate-400">
+            {FORMAT_LABELS[report.format]} · {report.entries.length} repos under{" "}
+            <span className="font-mono">{report.root}</span>
+            {Object.entries(counts).map(([status, count]) => (
+              <span key={status} className={`ml-2 ${OUTCOME_STYLES[status as keyof typeof OUTCOME_STYLES]}`}>
+                {count} {status}
+              </span>
+            ))}
+            {report.extra.length > 0 && (
+              <span className="ml-2 text-slate-300">{report.extra.length} extra</span>
+            )}
+          </p>
+          <ul className="mt-2 max-h-64 overflow-auto space-y-1">
+            {notable.map((entry, index) => (
+              <li key={`${entry.path}-${index}`} className="font-mono">
+                <span className={OUTCOME_STYLES[entry.outcome.status]}>{entry.outcome.status}</span>{" "}
+                <span className="text-slate-300">{entry.path}</span>
+                {details(entry).map((detail) => (
+                  <p key={detail} className="pl-4 text-slate-500 truncate" title={detail}>
+                    {detail}
+                  </p>
+                ))}
+              </li>
+            ))}
+            {report.extra.map((path) => (
+              <li key={path} className="font-mono">
+                <span className="text-slate-300">extra</span> <span className="text-slate-400">{path}</span>
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,7 +1,17 @@
 import { useState } from "react";
-import type { RepoInfo } from "../types";
+import type {
+  GitProgressEvent,
+  InProgressAction,
+  InProgressOperation,
+  RepoInfo,
+  RepoKind,
+  Severity,
+} from "../types";
+import DiagnosticsPanel from "./DiagnosticsPanel";
 import GitHubLink from "./GitHubLink";
 import StatusBadge from "./StatusBadge";
+import { FLAG_OPTIONS } from "./FilterBar";
+import { errorMessage } from "../utils/errors";
 
 function gitUrlToWeb(url: string | null): string | null {
   if (!url) return null;
@@ -18,15 +28,98 @@ function gitUrlToWeb(url: string | null): string | null {
   }
 }
 
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+function formatRelative(iso: string): string {
+  const diff = Date.now() - new Date(iso).getTime();
+  const mins = Math.floor(diff / 60000);
+  if (mins < 1) return "just now";
+  if (mins < 60) return `${mins}m ago`;
+  const hours = Math.floor(mins / 60);
+  if (hours < 24) return `${hours}h ago`;
+  const days = Math.floor(hours / 24);
+  if (days < 30) return `${days}d ago`;
+  const months = Math.floor(days / 30);
+  if (months < 12) return `${months}mo ago`;
+  return `${Math.floor(months / 12)}y ago`;
+}
+
+function describeProgress(p: GitProgressEvent): string {
+  switch (p.stage) {
+    case "transfer":
+      return p.received_objects < p.total_objects
+        ? `Receiving ${p.received_objects}/${p.total_objects} objects · ${formatBytes(p.received_bytes)}`
+        : `Resolving deltas ${p.indexed_deltas}/${p.total_deltas}`;
+    case "push":
+      return `Pushing ${p.current}/${p.total} objects · ${formatBytes(p.bytes)}`;
+    case "remote":
+      return p.message;
+  }
+}
+
+const IN_PROGRESS_LABELS: Record<InProgressOperation, string> = {
+  merge: "Merge",
+  rebase: "Rebase",
+  cherry_pick: "Cherry-pick",
+  revert: "Revert",
+  bisect: "Bisect",
+  apply_mailbox: "git am",
+};
+
+// Which actions GitAtlas can perform for each kind of interrupted operation
+const IN_PROGRESS_ACTIONS: Record<InProgressOperation, InProgressAction[]> = {
+  merge: ["continue", "abort"],
+  rebase: ["continue", "skip", "abort"],
+  cherry_pick: ["continue", "skip", "abort"],
+  revert: ["continue", "skip", "abort"],
+  bisect: ["skip", "abort"],
+  apply_mailbox: ["continue", "skip", "abort"],
+};
+
+const KIND_LABELS: Record<RepoKind, string> = {
+  standard: "",
+  worktree: "worktree of",
+  submodule: "submodule of",
+  separate_git_dir: "separate git dir",
+  bare: "bare",
+};
+
+function baseName(path: string): string {
+  return path.split(/[\\/]/).filter(Boolean).pop() ?? path;
+}
+
+const SEVERITY_STYLES: Record<Severity, string> = {
+  ok: "text-slate-400",
+  info: "text-sky-400",
+  warning: "text-amber-400",
+  error: "text-red-400",
+};
+
 interface RepoCardProps {
   repo: RepoInfo;
+  progress?: GitProgressEvent;
   onFetch: (path: string) => Promise<void>;
   onPullRebase: (path: string) => Promise<void>;
   onPush: (path: string) => Promise<void>;
+  onCancel: (path: string) => Promise<void>;
+  onResolve: (path: string, action: InProgressAction) => Promise<void>;
   onOpen: (repo: RepoInfo) => void;
 }
 
-export default function RepoCard({ repo, onFetch, onPullRebase, onPush, onOpen }: RepoCardProps) {
+export default function RepoCard({
+  repo,
+  progress,
+  onFetch,
+  onPullRebase,
+  onPush,
+  onCancel,
+  onResolve,
+  onOpen,
+}: RepoCardProps) {
   const [busy, setBusy] = useState<string | null>(null);
   const [actionError, setActionError] = useState<string | null>(null);
 
@@ -36,12 +129,21 @@ export default function RepoCard({ repo, onFetch, onPullRebase, onPush, onOpen }
     try {
       await action(repo.path);
     } catch (err) {
-      setActionError(String(err));
+      setActionError(errorMessage(err));
     } finally {
       setBusy(null);
     }
   };
 
+  // Drift from trunk is only interesting when trunk isn't what we track
+  const trunkDrift =
+    repo.default_branch !== null &&
+    repo.default_branch !== repo.upstream &&
+    repo.behind_default > 0;
+
+  // Detached is already shown on the branch pill
+  const flags = FLAG_OPTIONS.filter(({ value }) => value !== "detached" && repo[value]);
+
   return (
     <div
       className={`flex flex-col gap-3 rounded-lg border p-4 transition hover:border-slate-500 ${
@@ -65,30 +167,94 @@ export default function RepoCard({ repo, onFetch, onPullRebase, onPush, onOpen }
             <p className="truncate text-xs text-slate-500 font-mono" title={repo.path}>
               {repo.path}
             </p>
+            {repo.kind !== "standard" && (
+              <p
+                className={`truncate text-xs text-slate-400 ${repo.parent_path ? "pl-2 border-l border-slate-600" : ""}`}
+                title={repo.parent_path ?? undefined}
+              >
+                {KIND_LABELS[repo.kind]}
+                {repo.parent_path && ` ${baseName(repo.parent_path)}`}
+              </p>
+            )}
+            {repo.nested_in && repo.nested_in !== repo.parent_path && (
+              <p className="truncate text-xs text-slate-400" title={repo.nested_in}>
+                nested in {baseName(repo.nested_in)}
+              </p>
+            )}
           </div>
           <StatusBadge health={repo.health} />
         </div>
 
         <div className="flex items-center gap-2">
           <span className="truncate rounded bg-slate-700/60 px-2 py-0.5 text-xs font-mono text-blue-300">
-            {repo.branch}
+            {repo.detached ? `detached @ ${repo.branch}` : repo.branch}
           </span>
           {repo.behind > 0 && (
             <span className="rounded bg-red-900/60 px-2 py-0.5 text-xs font-medium text-red-300">
-              ↓{repo.behind} behind origin
+              ↓{repo.behind} behind {repo.upstream ?? "upstream"}
             </span>
           )}
         </div>
 
+        {flags.length > 0 && (
+          <div className="flex flex-wrap items-center gap-1.5">
+            {flags.map(({ value, label }) => (
+              <span
+                key={value}
+                className="rounded border border-slate-600 px-1.5 py-0.5 text-[10px] uppercase tracking-wide text-slate-400"
+              >
+                {label}
+              </span>
+            ))}
+          </div>
+        )}
+
         <div className="flex items-center gap-3 text-xs text-slate-400">
+          {trunkDrift && (
+            <span
+              className={repo.health === "behind_trunk" ? "text-amber-400" : "text-slate-500"}
+              title={`${repo.ahead_default} ahead of, ${repo.behind_default} behind ${repo.default_branch}`}
+            >
+              ↓{repo.behind_default} vs {repo.default_branch}
+            </span>
+          )}
           {repo.ahead > 0 && (
-            <span className="text-green-400" title="Commits ahead of origin">
+            <span className="text-green-400" title={`Commits ahead of ${repo.upstream ?? "upstream"}`}>
               ↑{repo.ahead} ahead
             </span>
           )}
-          {repo.dirty_files > 0 && (
-            <span className="text-yellow-400" title="Dirty files">
-              ~{repo.dirty_files} changed
+          {repo.staged_files > 0 && (
+            <span className="text-green-300" title="Staged files">
+              +{repo.staged_files} staged
+            </span>
+          )}
+          {repo.unstaged_files > 0 && (
+            <span className="text-yellow-400" title="Modified files not staged">
+              ~{repo.unstaged_files} modified
+            </span>
+          )}
+          {repo.untracked_files > 0 && (
+            <span className="text-slate-300" title="Untracked files (a directory counts once)">
+              ?{repo.untracked_files} untracked
+            </span>
+          )}
+          {repo.conflicted_files > 0 && (
+            <span className="text-red-400" title="Files with unresolved conflicts">
+              !{repo.conflicted_files} conflicted
+            </span>
+          )}
+          {repo.ignored_large_files > 0 && (
+            <span className="text-slate-500" title="Ignored files over 10 MiB, including inside ignored directories (searched to a limited depth), as of when the repo's detail view was last opened">
+              {repo.ignored_large_files} large ignored
+            </span>
+          )}
+          {(repo.submodules_drifted > 0 || repo.submodules_uninitialized > 0) && (
+            <span
+              className="text-amber-400"
+              title={`${repo.submodules_drifted} of ${repo.submodule_count} submodules not at the recorded commit, ${repo.submodules_uninitialized} not checked out`}
+            >
+              {repo.submodules_drifted + repo.submodules_uninitialized} submodule
+              {repo.submodules_drifted + repo.submodules_uninitialized === 1 ? "" : "s"} drifted
             </span>
           )}
           {repo.stash_count > 0 && (
@@ -96,12 +262,65 @@ export default function RepoCard({ repo, onFetch, onPullRebase, onPush, onOpen }
               {repo.stash_count} stash
             </span>
           )}
-          {repo.ahead === 0 && repo.behind === 0 && repo.dirty_files === 0 && repo.stash_count === 0 && (
+          {repo.ahead === 0 && repo.behind === 0 && repo.dirty_files === 0 && repo.stash_count === 0 && !trunkDrift && (
             <span className="text-slate-500">Up to date</span>
           )}
         </div>
+
+        {(repo.last_commit_at || repo.last_fetched_at) && (
+          <div className="flex items-center gap-3 text-[11px] text-slate-500">
+            {repo.last_commit_at && (
+              <span title={repo.last_activity_at ? `Last activity ${formatRelative(repo.last_activity_at)}` : undefined}>
+                Committed {formatRelative(repo.last_commit_at)}
+                {repo.last_commit_author && ` by ${repo.last_commit_author}`}
+              </span>
+            )}
+            <span>
+              {repo.last_fetched_at ? `Fetched ${formatRelative(repo.last_fetched_at)}` : "Never fetched"}
+            </span>
+          </div>
+        )}
       </div>
 
+      {repo.health_reasons.length > 0 && (
+        <ul className="flex flex-col gap-0.5 text-xs">
+          {repo.health_reasons.map((reason) => (
+            <li key={reason.rule} className={SEVERITY_STYLES[reason.severity]}>
+              • {reason.message}
+            </li>
+          ))}
+        </ul>
+      )}
+
+      {repo.health === "error" && <DiagnosticsPanel repo={repo} />}
+
+      {repo.in_progress && (
+        <div className="flex items-center gap-2 rounded border border-orange-900/50 bg-orange-950/30 px-2 py-1.5 text-xs">
+          <span className="flex-1 text-orange-300">
+            {IN_PROGRESS_LABELS[repo.in_progress]} in progress
+          </span>
+          {IN_PROGRESS_ACTIONS[repo.in_progress].map((action) => {
+            const label = action[0].toUpperCase() + action.slice(1);
+            return (
+              <button
+                key={action}
+                onClick={() => run(label, (path) => onResolve(path, action))}
+                disabled={busy !== null}
+                className="rounded bg-slate-700/60 px-2 py-0.5 font-medium text-slate-300 transition hover:bg-slate-600 disabled:opacity-40"
+              >
+                {busy === label ? `${label}...` : label}
+              </button>
+            );
+          })}
+        </div>
+      )}
+
+      {progress && (
+        <p className="text-xs text-slate-400 truncate" title={describeProgress(progress)}>
+          {describeProgress(progress)}
+        </p>
+      )}
+
       {actionError && (
         <p className="text-xs text-red-400 truncate" title={actionError}>
           {actionError}
@@ -112,6 +331,14 @@ export default function RepoCard({ repo, onFetch, onPullRebase, onPush, onOpen }
         <ActionButton label="Fetch" busy={busy} onClick={() => run("Fetch", onFetch)} />
         <ActionButton label="Pull" busy={busy} onClick={() => run("Pull", onPullRebase)} />
         <ActionButton label="Push" busy={busy} onClick={() => run("Push", onPush)} />
+        {busy && (
+          <button
+            onClick={() => onCancel(repo.path).catch(() => {})}
+            className="rounded px-2 py-1 text-xs font-medium text-red-300 transition hover:bg-red-900/40"
+          >
+            Cancel
+          </button>
+        )}
       </div>
     </div>
   );
@@ -1,15 +1,54 @@
-import type { RepoInfo } from "../types";
+import type { GitProgressEvent, InProgressAction, RepoInfo } from "../types";
 import RepoCard from "./RepoCard";
 
 interface RepoListProps {
   repos: RepoInfo[];
+  progress: Record<string, GitProgressEvent>;
   onFetch: (path: string) => Promise<void>;
   onPullRebase: (path: string) => Promise<void>;
   onPush: (path: string) => Promise<void>;
+  onCancel: (path: string) => Promise<void>;
+  onResolve: (path: string, action: InProgressAction) => Promise<void>;
   onOpen: (repo: RepoInfo) => void;
 }
 
-export default function RepoList({ repos, onFetch, onPullRebase, onPush, onOpen }: RepoListProps) {
+/**
+ * Order repos so linked worktrees and submodules come straight after the
+ * repo they belong to, keeping the incoming order otherwise. Children whose
+ * parent isn't in the list stay where they were.
+ */
+function groupByParent(repos: RepoInfo[]): RepoInfo[] {
+  const paths = new Set(repos.map((r) => r.path));
+  const children = new Map<string, RepoInfo[]>();
+  for (const repo of repos) {
+    if (repo.parent_path && paths.has(repo.parent_path)) {
+      const list = children.get(repo.parent_path) ?? [];
+      list.push(repo);
+      children.set(repo.parent_path, list);
+    }
+  }
+
+  const ordered: RepoInfo[] = [];
+  const visit = (repo: RepoInfo) => {
+    ordered.push(repo);
+    for (const child of children.get(repo.path) ?? []) visit(child);
+  };
+  for (const repo of repos) {
+    if (!(repo.parent_path && paths.has(repo.parent_path))) visit(repo);
+  }
+  return ordered;
+}
+
+export default function RepoList({
+  repos,
+  progress,
+  onFetch,
+  onPullRebase,
+  onPush,
+  onCancel,
+  onResolve,
+  onOpen,
+}: RepoListProps) {
   if (repos.length === 0) {
     return (
       <div className="flex flex-col items-center justify-center py-16 text-slate-500">
@@ -24,13 +63,16 @@ export default function RepoList({ repos, onFetch, onPullRebase, onPush, onOpen
       className="grid gap-3"
       style={{ gridTemplateColumns: "repeat(auto-fill, minmax(260px, 1fr))" }}
     >
-      {repos.map((repo) => (
+      {groupByParent(repos).map((repo) => (
         <RepoCard
           key={repo.path}
           repo={repo}
+          progress={progress[repo.path]}
           onFetch={onFetch}
           onPullRebase={onPullRebase}
           onPush={onPush}
+          onCancel={onCancel}
+          onResolve={onResolve}
           onOpen={onOpen}
         />
       ))}
@@ -4,6 +4,8 @@ const healthConfig: Record<RepoHealth, { color: string; label: string }> = {
   clean: { color: "bg-green-500", label: "Clean" },
   dirty: { color: "bg-yellow-500", label: "Changes" },
   diverged: { color: "bg-red-500", label: "Diverged" },
+  behind_trunk: { color: "bg-amber-600", label: "Behind trunk" },
+  in_progress: { color: "bg-orange-500", label: "In progress" },
   error: { color: "bg-gray-600", label: "Error" },
 };
 
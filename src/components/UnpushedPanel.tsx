@@ -0,0 +1,161 @@
+import { useEffect, useState } from "react";
+import type { ReportFormat, UnpushedBranch, UnpushedReport, UnpushedWork } from "../types";
+import { errorMessage } from "../utils/errors";
+
+interface UnpushedPanelProps {
+  onLoad: () => Promise<UnpushedReport>;
+  onExport: (file: string, format: ReportFormat) => Promise<UnpushedReport>;
+  onClose: () => void;
+}
+
+const plural = (count: number, noun: string) => `${count} ${noun}${count === 1 ? "" : "s"}`;
+
+function describeBranch(branch: UnpushedBranch): string {
+  const commits = plural(branch.unpushed_commits, "commit");
+  if (!branch.upstream) return `no upstream · ${commits} on no remote`;
+  if (branch.upstream_gone) return `${branch.upstream} is gone · ${commits} on no remote`;
+  return `${commits} ahead of ${branch.upstream}`;
+}
+
+function FileList({ label, files }: { label: string; files: string[] }) {
+  if (files.length === 0) return null;
+  return (
+    <details className="text-slate-400">
+      <summary className="cursor-pointer">{plural(files.length, label)}</summary>
+      <ul className="pl-4 font-mono text-slate-500">
+        {files.map((file) => (
+          <li key={file} className="truncate" title={file}>
+            {file}
+          </li>
+        ))}
+      </ul>
+    </details>
+  );
+}
+
+function RepoWork({ work }: { work: UnpushedWork }) {
+  return (
+    <div className="border-b border-slate-700/50 py-2">
+      <div className="flex items-baseline gap-2">
+        <span className="font-medium text-slate-200">{work.name}</span>
+        <span className="truncate font-mono text-slate-500" title={work.path}>
+          {work.path}
+        </span>
+      </div>
+      {work.error && <p className="text-red-400">{work.error}</p>}
+      {work.branches.map((branch) => (
+        <p key={branch.name} className="text-slate-400">
+          <span className="font-mono text-blue-300">{branch.name}</span>{" "}
+          <span className={branch.upstream && !branch.upstream_gone ? "text-green-400" : "text-amber-400"}>
+            {describeBranch(branch)}
+          </span>
+        </p>
+      ))}
+      {work.detached_commits > 0 && (
+        <p className="text-amber-400">Detached HEAD · {plural(work.detached_commits, "commit")} on no branch</p>
+      )}
+      {work.stashes.map((stash) => (
+        <p key={stash.index} className="truncate text-slate-400" title={stash.message}>
+          <span className="font-mono text-purple-300">stash@{`{${stash.index}}`}</span> {stash.message}
+        </p>
+      ))}
+      <FileList label="changed file" files={work.changed_files} />
+      <FileList label="untracked file" files={work.untracked_files} />
+    </div>
+  );
+}
+
+export default function UnpushedPanel({ onLoad, onExport, onClose }: UnpushedPanelProps) {
+  const [report, setReport] = useState<UnpushedReport | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [file, setFile] = useState("");
+  const [format, setFormat] = useState<ReportFormat>("markdown");
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = async (load: () => Promise<UnpushedReport>) => {
+    setLoading(true);
+    setError(null);
+    setMessage(null);
+    try {
+      setReport(await load());
+      return true;
+    } catch (err) {
+      setError(errorMessage(err));
+      return false;
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    run(onLoad);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  const handleExport = async () => {
+    const trimmed = file.trim();
+    if (!trimmed) return;
+    if (await run(() => onExport(trimmed, format))) setMessage(`Written to ${trimmed}`);
+  };
+
+  return (
+    <div className="mb-6 rounded-lg border border-slate-700 bg-slate-800/50 p-4 text-sm">
+      <div className="flex items-center gap-2">
+        <span className="flex-1 text-slate-300">
+          {loading
+            ? "Checking repos…"
+            : report &&
+              `Local-only work in ${report.repos.length} of ${plural(report.repos_checked, "repo")}`}
+        </span>
+        <button
+          onClick={() => run(onLoad)}
+          disabled={loading}
+          className="rounded-md border border-slate-600 px-3 py-1 text-slate-300 transition hover:text-white disabled:opacity-50"
+        >
+          Refresh
+        </button>
+        <button onClick={onClose} className="px-1 text-slate-500 hover:text-slate-300" title="Close">
+          ✕
+        </button>
+      </div>
+
+      <div className="mt-2 flex items-center gap-2">
+        <input
+          value={file}
+          onChange={(e) => setFile(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleExport()}
+          placeholder="Export to file…"
+          disabled={loading}
+          className="flex-1 min-w-0 rounded bg-slate-800 border border-slate-600 px-2 py-1 font-mono text-slate-200 placeholder-slate-500 focus:border-indigo-500 focus:outline-none"
+        />
+        <select
+          value={format}
+          onChange={(e) => setFormat(e.target.value as ReportFormat)}
+          disabled={loading}
+          className="rounded bg-slate-800 border border-slate-600 px-2 py-1 text-slate-300"
+        >
+          <option value="markdown">Markdown</option>
+          <option value="json">JSON</option>
+        </select>
+        <button
+          onClick={handleExport}
+          disabled={loading || !file.trim()}
+          className="rounded-md bg-indigo-600 px-3 py-1 font-medium text-white transition hover:bg-indigo-500 disabled:opacity-50"
+        >
+          Export
+        </button>
+      </div>
+      {message && <p className="mt-1 text-xs text-green-400">{message}</p>}
+      {error && <p className="mt-1 text-xs text-red-400">{error}</p>}
+
+      {report && report.repos.length > 0 && (
+        <div className="mt-3 max-h-96 overflow-auto text-xs">
+          {report.repos.map((work) => (
+            <RepoWork key={work.path} work={work} />
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
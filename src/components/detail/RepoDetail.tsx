@@ -7,9 +7,11 @@ import DiffViewer from "./DiffViewer";
 import CommitForm from "./CommitForm";
 import BranchPanel from "./BranchPanel";
 import StashPanel from "./StashPanel";
+import SubmodulePanel from "./SubmodulePanel";
+import WorktreePanel from "./WorktreePanel";
 import ReadmeViewer from "./ReadmeViewer";
 
-type Tab = "changes" | "history" | "branches" | "stashes" | "readme";
+type Tab = "changes" | "history" | "branches" | "worktrees" | "stashes" | "submodules" | "readme";
 
 interface RepoDetailProps {
   repo: RepoInfo;
@@ -39,8 +41,13 @@ export default function RepoDetail({ repo, onClose }: RepoDetailProps) {
     } else if (activeTab === "branches") {
       detail.loadBranches();
       detail.loadRemotes();
+    } else if (activeTab === "worktrees") {
+      detail.loadWorktrees();
+      detail.loadBranches();
     } else if (activeTab === "stashes") {
       detail.loadStashes();
+    } else if (activeTab === "submodules") {
+      detail.loadSubmodules();
     } else if (activeTab === "readme") {
       detail.loadReadme();
     }
@@ -115,9 +122,9 @@ export default function RepoDetail({ repo, onClose }: RepoDetailProps) {
       const tag = (e.target as HTMLElement)?.tagName;
       if (tag === "INPUT" || tag === "TEXTAREA" || tag === "SELECT") return;
 
-      // Tab switching: 1–5
-      const tabKeys: Tab[] = ["changes", "history", "branches", "stashes", "readme"];
-      if (e.key >= "1" && e.key <= "5") {
+      // Tab switching: 1–7
+      const tabKeys: Tab[] = ["changes", "history", "branches", "worktrees", "stashes", "submodules", "readme"];
+      if (e.key >= "1" && e.key <= "7") {
         e.preventDefault();
         setActiveTab(tabKeys[parseInt(e.key) - 1]);
         return;
@@ -210,7 +217,9 @@ export default function RepoDetail({ repo, onClose }: RepoDetailProps) {
     { key: "changes", label: "Changes" },
     { key: "history", label: "History" },
     { key: "branches", label: "Branches" },
+    { key: "worktrees", label: "Worktrees" },
     { key: "stashes", label: "Stashes" },
+    { key: "submodules", label: "Submodules" },
     { key: "readme", label: "Readme" },
   ];
 
@@ -479,6 +488,21 @@ export default function RepoDetail({ repo, onClose }: RepoDetailProps) {
           </div>
         )}
 
+        {activeTab === "worktrees" && (
+          <div className="flex-1 overflow-auto">
+            <WorktreePanel
+              repoPath={repo.path}
+              worktrees={detail.worktrees}
+              branches={detail.branches}
+              onAdd={detail.addWorktree}
+              onLock={detail.lockWorktree}
+              onUnlock={detail.unlockWorktree}
+              onPrune={detail.pruneWorktrees}
+              onRemove={detail.removeWorktree}
+            />
+          </div>
+        )}
+
         {activeTab === "stashes" && (
           <div className="flex-1 overflow-auto">
             <StashPanel
@@ -490,6 +514,18 @@ export default function RepoDetail({ repo, onClose }: RepoDetailProps) {
           </div>
         )}
 
+        {activeTab === "submodules" && (
+          <div className="flex-1 overflow-auto">
+            <SubmodulePanel
+              submodules={detail.submodules}
+              onInit={detail.initSubmodules}
+              onUpdate={detail.updateSubmodules}
+              onSync={detail.syncSubmodules}
+              onDeinit={detail.deinitSubmodule}
+            />
+          </div>
+        )}
+
         {activeTab === "readme" && (
           <div className="flex-1 overflow-auto">
             <ReadmeViewer content={detail.readme} />
@@ -0,0 +1,122 @@
+import type { SubmoduleInfo } from "../../types";
+
+interface SubmodulePanelProps {
+  submodules: SubmoduleInfo[];
+  onInit: (names: string[]) => Promise<void>;
+  onUpdate: (names: string[]) => Promise<void>;
+  onSync: (names: string[]) => Promise<void>;
+  onDeinit: (name: string, force: boolean) => Promise<void>;
+}
+
+const shortOid = (oid: string | null) => (oid ? oid.slice(0, 7) : "—");
+
+function stateLabels(sub: SubmoduleInfo): { label: string; className: string }[] {
+  const labels: { label: string; className: string }[] = [];
+  if (!sub.initialized) labels.push({ label: "not initialized", className: "text-slate-500" });
+  else if (!sub.checked_out) labels.push({ label: "not checked out", className: "text-amber-400" });
+  if (sub.drifted) labels.push({ label: "drifted", className: "text-amber-400" });
+  if (sub.dirty) labels.push({ label: "dirty", className: "text-yellow-400" });
+  if (sub.out_of_sync) labels.push({ label: "url out of sync", className: "text-sky-400" });
+  return labels;
+}
+
+export default function SubmodulePanel({
+  submodules,
+  onInit,
+  onUpdate,
+  onSync,
+  onDeinit,
+}: SubmodulePanelProps) {
+  const needsUpdate = submodules.some((s) => !s.checked_out || s.drifted);
+  const needsSync = submodules.some((s) => s.out_of_sync);
+
+  return (
+    <div className="flex flex-col h-full overflow-hidden">
+      <div className="flex items-center gap-2 px-3 py-2 border-b border-slate-700">
+        <span className="flex-1 text-xs text-slate-400">
+          {submodules.length} submodule{submodules.length === 1 ? "" : "s"}
+        </span>
+        <button
+          onClick={() => onSync([])}
+          disabled={!needsSync}
+          className="rounded bg-slate-700 px-2.5 py-1 text-xs text-slate-300 hover:bg-slate-600 disabled:opacity-40"
+        >
+          Sync all
+        </button>
+        <button
+          onClick={() => onUpdate([])}
+          disabled={!needsUpdate}
+          className="rounded bg-blue-600 px-2.5 py-1 text-xs font-medium text-white hover:bg-blue-500 disabled:opacity-40"
+        >
+          Update all
+        </button>
+      </div>
+
+      <div className="overflow-auto flex-1">
+        {submodules.length === 0 ? (
+          <p className="px-3 py-4 text-xs text-slate-500 text-center">No submodules</p>
+        ) : (
+          submodules.map((sub) => (
+            <div
+              key={sub.name}
+              className="group flex items-center gap-2 px-3 py-2 text-xs border-b border-slate-700/30 hover:bg-slate-700/30 transition"
+            >
+              <div className="flex-1 min-w-0">
+                <div className="flex items-center gap-2">
+                  <span className="truncate font-mono text-slate-200">{sub.path}</span>
+                  {stateLabels(sub).map(({ label, className }) => (
+                    <span key={label} className={className}>
+                      {label}
+                    </span>
+                  ))}
+                </div>
+                <p className="truncate text-slate-500" title={sub.url ?? undefined}>
+                  {sub.url ?? "no url"}
+                  {sub.branch && ` · ${sub.branch}`}
+                </p>
+                <p className="font-mono text-slate-500">
+                  recorded {shortOid(sub.recorded_commit)} · checked out {shortOid(sub.checked_out_commit)}
+                </p>
+              </div>
+              <div className="hidden group-hover:flex items-center gap-1">
+                {!sub.initialized && (
+                  <button
+                    onClick={() => onInit([sub.name])}
+                    className="rounded bg-slate-700 px-1.5 py-0.5 text-slate-300 hover:bg-slate-600"
+                  >
+                    init
+                  </button>
+                )}
+                <button
+                  onClick={() => onUpdate([sub.name])}
+                  className="rounded bg-slate-700 px-1.5 py-0.5 text-slate-300 hover:bg-slate-600"
+                >
+                  update
+                </button>
+                {sub.out_of_sync && (
+                  <button
+                    onClick={() => onSync([sub.name])}
+                    className="rounded bg-slate-700 px-1.5 py-0.5 text-slate-300 hover:bg-slate-600"
+                  >
+                    sync
+                  </button>
+                )}
+                {sub.checked_out && (
+                  <button
+                    onClick={() => {
+                      if (sub.dirty && !confirm(`Discard local changes in ${sub.path}?`)) return;
+                      onDeinit(sub.name, sub.dirty);
+                    }}
+                    className="rounded bg-red-900/50 px-1.5 py-0.5 text-red-400 hover:bg-red-900"
+                  >
+                    deinit
+                  </button>
+                )}
+              </div>
+            </div>
+          ))
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,225 @@
+import { useState } from "react";
+import type { BranchInfo, WorktreeInfo } from "../../types";
+import StatusBadge from "../StatusBadge";
+
+interface WorktreePanelProps {
+  repoPath: string;
+  worktrees: WorktreeInfo[];
+  branches: BranchInfo[];
+  onAdd: (worktreePath: string, branchName: string, createBranch: boolean) => Promise<void>;
+  onLock: (name: string, reason: string | null) => Promise<void>;
+  onUnlock: (name: string) => Promise<void>;
+  onPrune: () => Promise<void>;
+  onRemove: (name: string, force: boolean) => Promise<void>;
+}
+
+// Next to the main checkout, like `git worktree add ../repo-branch`
+function suggestedPath(mainPath: string, branch: string): string {
+  const trimmed = mainPath.replace(/[\\/]+$/, "");
+  const slug = branch.replace(/[^\w.-]+/g, "-");
+  return slug ? `${trimmed}-${slug}` : "";
+}
+
+export default function WorktreePanel({
+  repoPath,
+  worktrees,
+  branches,
+  onAdd,
+  onLock,
+  onUnlock,
+  onPrune,
+  onRemove,
+}: WorktreePanelProps) {
+  const [showAdd, setShowAdd] = useState(false);
+  const [branch, setBranch] = useState("");
+  const [createBranch, setCreateBranch] = useState(false);
+  const [path, setPath] = useState("");
+  const [adding, setAdding] = useState(false);
+
+  const mainPath = worktrees.find((w) => w.is_main)?.path ?? repoPath;
+  // A branch can only be checked out in one worktree at a time
+  const checkedOut = new Set(worktrees.map((w) => w.status?.branch));
+  const available = branches.filter((b) => !b.is_remote && !checkedOut.has(b.name));
+  const hasPrunable = worktrees.some((w) => w.prunable && !w.locked);
+
+  const chooseBranch = (name: string) => {
+    setBranch(name);
+    setPath(suggestedPath(mainPath, name));
+  };
+
+  const handleAdd = async () => {
+    if (!branch.trim() || !path.trim()) return;
+    setAdding(true);
+    try {
+      await onAdd(path.trim(), branch.trim(), createBranch);
+      setBranch("");
+      setPath("");
+      setShowAdd(false);
+    } finally {
+      setAdding(false);
+    }
+  };
+
+  const handleRemove = (worktree: WorktreeInfo) => {
+    if (!worktree.name) return;
+    const dirty = (worktree.status?.dirty_files ?? 0) > 0;
+    const force = dirty || worktree.locked;
+    if (force && !confirm(`Remove ${worktree.path}? It is ${dirty ? "dirty" : "locked"}.`)) return;
+    onRemove(worktree.name, force);
+  };
+
+  return (
+    <div className="flex flex-col h-full overflow-hidden">
+      <div className="flex items-center justify-between px-3 py-2 border-b border-slate-700">
+        <span className="text-xs font-semibold text-slate-400 uppercase tracking-wide">
+          Worktrees
+          <span className="ml-1.5 text-slate-500">{worktrees.length}</span>
+        </span>
+        <div className="flex items-center gap-3">
+          <button
+            onClick={onPrune}
+            disabled={!hasPrunable}
+            className="text-xs text-slate-400 hover:text-slate-200 transition disabled:opacity-40"
+          >
+            Prune
+          </button>
+          <button
+            onClick={() => setShowAdd(!showAdd)}
+            className="text-xs text-slate-400 hover:text-slate-200 transition"
+          >
+            + Add
+          </button>
+        </div>
+      </div>
+
+      {showAdd && (
+        <div className="flex flex-col gap-2 px-3 py-2 border-b border-slate-700/50">
+          <div className="flex items-center gap-2">
+            {createBranch ? (
+              <input
+                value={branch}
+                onChange={(e) => chooseBranch(e.target.value)}
+                placeholder="New branch name"
+                className="flex-1 rounded border border-slate-700 bg-slate-800 px-2 py-1 text-xs text-slate-200 placeholder-slate-500 outline-none focus:border-slate-500"
+                autoFocus
+              />
+            ) : (
+              <select
+                value={branch}
+                onChange={(e) => chooseBranch(e.target.value)}
+                className="flex-1 rounded border border-slate-700 bg-slate-800 px-2 py-1 text-xs text-slate-200 outline-none focus:border-slate-500"
+              >
+                <option value="">Choose a branch…</option>
+                {available.map((b) => (
+                  <option key={b.name} value={b.name}>
+                    {b.name}
+                  </option>
+                ))}
+              </select>
+            )}
+            <label className="flex items-center gap-1 text-xs text-slate-400">
+              <input
+                type="checkbox"
+                checked={createBranch}
+                onChange={(e) => {
+                  setCreateBranch(e.target.checked);
+                  chooseBranch("");
+                }}
+              />
+              new branch
+            </label>
+          </div>
+          <div className="flex items-center gap-2">
+            <input
+              value={path}
+              onChange={(e) => setPath(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+              placeholder="Worktree path"
+              className="flex-1 rounded border border-slate-700 bg-slate-800 px-2 py-1 text-xs font-mono text-slate-200 placeholder-slate-500 outline-none focus:border-slate-500"
+            />
+            <button
+              onClick={handleAdd}
+              disabled={adding || !branch.trim() || !path.trim()}
+              className="rounded bg-blue-600 px-2.5 py-1 text-xs font-medium text-white hover:bg-blue-500 disabled:opacity-40"
+            >
+              {adding ? "..." : "Add"}
+            </button>
+          </div>
+        </div>
+      )}
+
+      <div className="overflow-auto flex-1">
+        {worktrees.map((worktree) => (
+          <div
+            key={worktree.path}
+            className="group flex items-center gap-2 px-3 py-2 text-xs border-b border-slate-700/30 hover:bg-slate-700/30 transition"
+          >
+            {worktree.status ? (
+              <StatusBadge health={worktree.status.health} />
+            ) : (
+              <span className="text-slate-600">○</span>
+            )}
+            <div className="flex-1 min-w-0">
+              <div className="flex items-center gap-2">
+                <span className="truncate font-mono text-blue-300">
+                  {worktree.status?.branch ?? "—"}
+                </span>
+                {worktree.is_main && <span className="text-slate-500">main</span>}
+                {worktree.locked && (
+                  <span className="text-amber-400" title={worktree.lock_reason ?? undefined}>
+                    locked
+                  </span>
+                )}
+                {worktree.prunable && <span className="text-red-400">missing</span>}
+              </div>
+              <p className="truncate font-mono text-slate-500" title={worktree.path}>
+                {worktree.path}
+              </p>
+              {worktree.status && (
+                <p className="flex items-center gap-2 text-slate-400">
+                  {worktree.status.ahead > 0 && (
+                    <span className="text-green-400">↑{worktree.status.ahead}</span>
+                  )}
+                  {worktree.status.behind > 0 && (
+                    <span className="text-red-400">↓{worktree.status.behind}</span>
+                  )}
+                  {worktree.status.dirty_files > 0 && (
+                    <span className="text-yellow-400">{worktree.status.dirty_files} changed</span>
+                  )}
+                  {worktree.status.ahead === 0 &&
+                    worktree.status.behind === 0 &&
+                    worktree.status.dirty_files === 0 && <span className="text-slate-500">Up to date</span>}
+                </p>
+              )}
+            </div>
+            {worktree.name && (
+              <div className="hidden group-hover:flex items-center gap-1">
+                {worktree.locked ? (
+                  <button
+                    onClick={() => onUnlock(worktree.name!)}
+                    className="rounded bg-slate-700 px-1.5 py-0.5 text-slate-300 hover:bg-slate-600"
+                  >
+                    unlock
+                  </button>
+                ) : (
+                  <button
+                    onClick={() => onLock(worktree.name!, prompt("Lock reason (optional)") || null)}
+                    className="rounded bg-slate-700 px-1.5 py-0.5 text-slate-300 hover:bg-slate-600"
+                  >
+                    lock
+                  </button>
+                )}
+                <button
+                  onClick={() => handleRemove(worktree)}
+                  className="rounded bg-red-900/50 px-1.5 py-0.5 text-red-400 hover:bg-red-900"
+                >
+                  remove
+                </button>
+              </div>
+            )}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -7,8 +7,11 @@ import type {
   StashEntry,
   CommitFileChange,
   RemoteInfo,
+  SubmoduleInfo,
+  WorktreeInfo,
   GitProfile,
 } from "../types";
+import { errorMessage } from "../utils/errors";
 
 export function useRepoDetail(repoPath: string) {
   const [commits, setCommits] = useState<CommitInfo[]>([]);
@@ -28,13 +31,15 @@ export function useRepoDetail(repoPath: string) {
   const [fileHistoryPath, setFileHistoryPath] = useState<string | null>(null);
   const [remotes, setRemotes] = useState<RemoteInfo[]>([]);
   const [profile, setProfile] = useState<GitProfile | null>(null);
+  const [submodules, setSubmodules] = useState<SubmoduleInfo[]>([]);
+  const [worktrees, setWorktrees] = useState<WorktreeInfo[]>([]);
 
   const withError = useCallback(async (fn: () => Promise<void>) => {
     setError(null);
     try {
       await fn();
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     }
   }, []);
 
@@ -279,6 +284,72 @@ export function useRepoDetail(repoPath: string) {
     setLoading(false);
   }, [repoPath, withError, loadCommits, showSuccess]);
 
+  // ── Worktrees ──
+
+  const loadWorktrees = useCallback(async () => {
+    await withError(async () => {
+      const result = await invoke<WorktreeInfo[]>("get_worktrees", {
+        path: repoPath,
+      });
+      setWorktrees(result);
+    });
+  }, [repoPath, withError]);
+
+  const addWorktree = useCallback(
+    async (worktreePath: string, branchName: string, createBranch: boolean) => {
+      await withError(async () => {
+        const result = await invoke<WorktreeInfo[]>("add_worktree", {
+          path: repoPath,
+          worktreePath,
+          branchName,
+          createBranch,
+        });
+        setWorktrees(result);
+        await loadBranches();
+        showSuccess(`Worktree added at ${worktreePath}`);
+      });
+    },
+    [repoPath, withError, loadBranches, showSuccess],
+  );
+
+  const lockWorktree = useCallback(
+    async (name: string, reason: string | null) => {
+      await withError(async () => {
+        const result = await invoke<WorktreeInfo[]>("lock_worktree", { path: repoPath, name, reason });
+        setWorktrees(result);
+      });
+    },
+    [repoPath, withError],
+  );
+
+  const unlockWorktree = useCallback(
+    async (name: string) => {
+      await withError(async () => {
+        const result = await invoke<WorktreeInfo[]>("unlock_worktree", { path: repoPath, name });
+        setWorktrees(result);
+      });
+    },
+    [repoPath, withError],
+  );
+
+  const pruneWorktrees = useCallback(async () => {
+    await withError(async () => {
+      const result = await invoke<WorktreeInfo[]>("prune_worktrees", { path: repoPath });
+      setWorktrees(result);
+    });
+  }, [repoPath, withError]);
+
+  const removeWorktree = useCallback(
+    async (name: string, force: boolean) => {
+      await withError(async () => {
+        const result = await invoke<WorktreeInfo[]>("remove_worktree", { path: repoPath, name, force });
+        setWorktrees(result);
+        showSuccess(`Worktree '${name}' removed`);
+      });
+    },
+    [repoPath, withError, showSuccess],
+  );
+
   // ── Stashes ──
 
   const loadStashes = useCallback(async () => {
@@ -322,6 +393,63 @@ export function useRepoDetail(repoPath: string) {
     [repoPath, withError, loadStashes],
   );
 
+  // ── Submodules ──
+
+  const loadSubmodules = useCallback(async () => {
+    await withError(async () => {
+      const result = await invoke<SubmoduleInfo[]>("get_submodules", {
+        path: repoPath,
+      });
+      setSubmodules(result);
+    });
+  }, [repoPath, withError]);
+
+  const initSubmodules = useCallback(
+    async (names: string[]) => {
+      await withError(async () => {
+        const result = await invoke<SubmoduleInfo[]>("init_submodules", { path: repoPath, names });
+        setSubmodules(result);
+      });
+    },
+    [repoPath, withError],
+  );
+
+  const syncSubmodules = useCallback(
+    async (names: string[]) => {
+      await withError(async () => {
+        const result = await invoke<SubmoduleInfo[]>("sync_submodules", { path: repoPath, names });
+        setSubmodules(result);
+        showSuccess("Submodule URLs synced");
+      });
+    },
+    [repoPath, withError, showSuccess],
+  );
+
+  const updateSubmodules = useCallback(
+    async (names: string[]) => {
+      setLoading(true);
+      setLoadingAction("Updating submodules…");
+      await withError(async () => {
+        await invoke("update_submodules", { path: repoPath, names, init: true });
+        await loadSubmodules();
+        showSuccess("Submodules updated");
+      });
+      setLoadingAction(null);
+      setLoading(false);
+    },
+    [repoPath, withError, loadSubmodules, showSuccess],
+  );
+
+  const deinitSubmodule = useCallback(
+    async (name: string, force: boolean) => {
+      await withError(async () => {
+        const result = await invoke<SubmoduleInfo[]>("deinit_submodule", { path: repoPath, name, force });
+        setSubmodules(result);
+      });
+    },
+    [repoPath, withError],
+  );
+
   // ── README ──
 
   const loadReadme = useCallback(async () => {
@@ -467,6 +595,8 @@ export function useRepoDetail(repoPath: string) {
     fileHistoryPath,
     remotes,
     profile,
+    submodules,
+    worktrees,
     setDiff,
     loadCommits,
     loadCommitDiff,
@@ -486,10 +616,21 @@ export function useRepoDetail(repoPath: string) {
     fetchRemote,
     pullRebase,
     push,
+    loadWorktrees,
+    addWorktree,
+    lockWorktree,
+    unlockWorktree,
+    pruneWorktrees,
+    removeWorktree,
     loadStashes,
     saveStash,
     popStash,
     dropStash,
+    loadSubmodules,
+    initSubmodules,
+    syncSubmodules,
+    updateSubmodules,
+    deinitSubmodule,
     loadReadme,
     loadFileHistory,
     closeFileHistory,
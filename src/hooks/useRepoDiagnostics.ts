@@ -0,0 +1,25 @@
+import { useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { RepoDiagnostics } from "../types";
+import { errorMessage } from "../utils/errors";
+
+export function useRepoDiagnostics(path: string) {
+  const [diagnostics, setDiagnostics] = useState<RepoDiagnostics | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = useCallback(async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await invoke<RepoDiagnostics>("diagnose_repo", { path });
+      setDiagnostics(result);
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setLoading(false);
+    }
+  }, [path]);
+
+  return { diagnostics, loading, error, run };
+}
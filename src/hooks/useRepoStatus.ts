@@ -1,6 +1,7 @@
 import { useState, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import type { RepoInfo } from "../types";
+import { errorMessage } from "../utils/errors";
 
 export function useRepoStatus(path: string) {
   const [status, setStatus] = useState<RepoInfo | null>(null);
@@ -14,7 +15,7 @@ export function useRepoStatus(path: string) {
       const result = await invoke<RepoInfo>("get_repo_status", { path });
       setStatus(result);
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -1,11 +1,37 @@
-import { useState, useCallback, useEffect } from "react";
+import { useState, useCallback, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { RepoInfo } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  BulkReport,
+  BulkRepoEvent,
+  GitProgressEvent,
+  InProgressAction,
+  ManifestSyncReport,
+  RepoInfo,
+  RepoQuery,
+  ReportFormat,
+  ScanEvent,
+  UnpushedReport,
+} from "../types";
+import { errorMessage, isAppError } from "../utils/errors";
+
+export type ScanProgress = Pick<ScanEvent, "dirs_visited" | "discovered" | "completed">;
+
+// Single-repo operations are keyed by path so a card can cancel its own run
+const repoOperationId = (path: string) => `repo:${path}`;
+const cloneOperationId = (url: string) => `clone:${url}`;
+const MANIFEST_OPERATION_ID = "manifest-sync";
 
 export function useRepos() {
   const [repos, setRepos] = useState<RepoInfo[]>([]);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [bulkReport, setBulkReport] = useState<BulkReport | null>(null);
+  const [progress, setProgress] = useState<Record<string, GitProgressEvent>>({});
+  const [watching, setWatching] = useState(false);
+  const [scanProgress, setScanProgress] = useState<ScanProgress | null>(null);
+  const bulkOperationId = useRef<string | null>(null);
+  const scanOperationId = useRef<string | null>(null);
 
   // Load cached repos on mount for instant startup
   useEffect(() => {
@@ -16,83 +42,273 @@ export function useRepos() {
       .catch(() => {});
   }, []);
 
+  useEffect(() => {
+    invoke<boolean>("is_watching").then(setWatching).catch(() => {});
+  }, []);
+
+  // Latest network progress per repo path, cleared when the operation ends
+  useEffect(() => {
+    const unlisten = listen<GitProgressEvent>("git-progress", (event) => {
+      setProgress((prev) => ({ ...prev, [event.payload.path]: event.payload }));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const clearProgress = useCallback((path: string) => {
+    setProgress((prev) => {
+      const next = { ...prev };
+      delete next[path];
+      return next;
+    });
+  }, []);
+
   const updateRepo = useCallback((updated: RepoInfo) => {
     setRepos((prev) =>
       prev.map((r) => (r.path === updated.path ? updated : r)),
     );
   }, []);
 
-  const scanRepos = useCallback(async (roots: string[]) => {
-    setLoading(true);
+  // Live updates pushed by the filesystem watcher
+  useEffect(() => {
+    const unlisten = listen<RepoInfo>("repo-updated", (event) => {
+      updateRepo(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [updateRepo]);
+
+  const toggleWatching = useCallback(async () => {
     setError(null);
     try {
-      const result = await invoke<RepoInfo[]>("scan_directories", { roots });
-      setRepos(result);
+      if (watching) {
+        await invoke("stop_watching");
+        setWatching(false);
+      } else {
+        await invoke<number>("start_watching");
+        setWatching(true);
+      }
     } catch (err) {
-      setError(String(err));
-    } finally {
-      setLoading(false);
+      setError(errorMessage(err));
     }
-  }, []);
+  }, [watching]);
 
-  const refreshRepos = useCallback(async () => {
+  // Scans stream repos in as their status completes; the final result also
+  // drops repos that no longer exist
+  const scanRepos = useCallback(async (full = false) => {
     setLoading(true);
     setError(null);
+    const operationId = `scan:${Date.now()}`;
+    scanOperationId.current = operationId;
+    const unlisten = await listen<ScanEvent>("scan-event", (event) => {
+      const payload = event.payload;
+      if (payload.operation_id !== operationId) return;
+      setScanProgress({
+        dirs_visited: payload.dirs_visited,
+        discovered: payload.discovered,
+        completed: payload.completed,
+      });
+      if (payload.kind === "completed") {
+        const repo = payload.repo;
+        setRepos((prev) =>
+          prev.some((r) => r.path === repo.path)
+            ? prev.map((r) => (r.path === repo.path ? repo : r))
+            : [...prev, repo],
+        );
+      }
+    });
     try {
-      const result = await invoke<RepoInfo[]>("get_all_repos");
+      const result = await invoke<RepoInfo[]>("scan_directories", { full, operationId });
       setRepos(result);
     } catch (err) {
-      setError(String(err));
+      if (!(isAppError(err) && err.code === "cancelled")) {
+        setError(errorMessage(err));
+      }
     } finally {
+      unlisten();
+      scanOperationId.current = null;
+      setScanProgress(null);
       setLoading(false);
     }
   }, []);
 
-  const fetchAll = useCallback(async () => {
+  const cancelScan = useCallback(async () => {
+    if (scanOperationId.current) {
+      await invoke("cancel_operation", { operationId: scanOperationId.current });
+    }
+  }, []);
+
+  const refreshRepos = useCallback(async () => {
     setLoading(true);
     setError(null);
     try {
-      const result = await invoke<RepoInfo[]>("fetch_all");
+      const result = await invoke<RepoInfo[]>("get_all_repos");
       setRepos(result);
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
   }, []);
 
-  const pullAll = useCallback(async () => {
-    setLoading(true);
+  // Reload the stored repo list sorted/filtered by the database
+  const queryRepos = useCallback(async (query: RepoQuery) => {
     setError(null);
     try {
-      const result = await invoke<RepoInfo[]>("pull_all");
+      const result = await invoke<RepoInfo[]>("query_repos", { query });
       setRepos(result);
     } catch (err) {
-      setError(String(err));
-    } finally {
-      setLoading(false);
+      setError(errorMessage(err));
     }
   }, []);
 
-  const fetchRepo = useCallback(
-    async (path: string) => {
-      const updated = await invoke<RepoInfo>("fetch_repo", { path });
-      updateRepo(updated);
+  // Bulk operations stream each repo's result as it finishes
+  const runBulk = useCallback(
+    async (command: "fetch_all" | "pull_all") => {
+      setLoading(true);
+      setError(null);
+      setBulkReport(null);
+      const operationId = `${command}:${Date.now()}`;
+      bulkOperationId.current = operationId;
+      const unlisten = await listen<BulkRepoEvent>("bulk-repo-result", (event) => {
+        updateRepo(event.payload.result.repo);
+        clearProgress(event.payload.result.path);
+      });
+      try {
+        const report = await invoke<BulkReport>(command, { operationId });
+        setRepos(report.results.map((r) => r.repo));
+        setBulkReport(report);
+      } catch (err) {
+        setError(errorMessage(err));
+      } finally {
+        unlisten();
+        bulkOperationId.current = null;
+        setLoading(false);
+      }
     },
-    [updateRepo],
+    [updateRepo, clearProgress],
+  );
+
+  const cancelBulk = useCallback(async () => {
+    if (bulkOperationId.current) {
+      await invoke("cancel_operation", { operationId: bulkOperationId.current });
+    }
+  }, []);
+
+  const fetchAll = useCallback(() => runBulk("fetch_all"), [runBulk]);
+
+  const pullAll = useCallback(() => runBulk("pull_all"), [runBulk]);
+
+  const runRepoOperation = useCallback(
+    async (command: "fetch_repo" | "pull_rebase_repo" | "push_repo", path: string) => {
+      try {
+        const updated = await invoke<RepoInfo>(command, {
+          path,
+          operationId: repoOperationId(path),
+        });
+        updateRepo(updated);
+      } finally {
+        clearProgress(path);
+      }
+    },
+    [updateRepo, clearProgress],
+  );
+
+  const fetchRepo = useCallback(
+    (path: string) => runRepoOperation("fetch_repo", path),
+    [runRepoOperation],
   );
 
   const pullRebaseRepo = useCallback(
-    async (path: string) => {
-      const updated = await invoke<RepoInfo>("pull_rebase_repo", { path });
+    (path: string) => runRepoOperation("pull_rebase_repo", path),
+    [runRepoOperation],
+  );
+
+  const pushRepo = useCallback(
+    (path: string) => runRepoOperation("push_repo", path),
+    [runRepoOperation],
+  );
+
+  const cancelRepoOperation = useCallback(async (path: string) => {
+    await invoke("cancel_operation", { operationId: repoOperationId(path) });
+  }, []);
+
+  // Clone into a scan root; the new repo joins the list without a rescan
+  const cloneRepo = useCallback(
+    async (url: string, root: string | null) => {
+      const dest = await invoke<string>("get_clone_destination", { url, root });
+      try {
+        const cloned = await invoke<RepoInfo>("clone_repo", {
+          url,
+          root,
+          operationId: cloneOperationId(url),
+        });
+        setRepos((prev) => [...prev.filter((r) => r.path !== cloned.path), cloned]);
+        return cloned;
+      } finally {
+        clearProgress(dest);
+      }
+    },
+    [clearProgress],
+  );
+
+  const cancelClone = useCallback(async (url: string) => {
+    await invoke("cancel_operation", { operationId: cloneOperationId(url) });
+  }, []);
+
+  const exportManifest = useCallback(async (file: string) => {
+    return invoke<number>("export_manifest", { file });
+  }, []);
+
+  // Present and cloned repos join the list, so reload it from the database afterwards
+  const syncManifest = useCallback(
+    async (file: string, root: string | null, cloneMissing: boolean) => {
+      try {
+        return await invoke<ManifestSyncReport>("sync_manifest", {
+          file,
+          root,
+          cloneMissing,
+          operationId: MANIFEST_OPERATION_ID,
+        });
+      } finally {
+        setProgress((prev) =>
+          Object.fromEntries(
+            Object.entries(prev).filter(([, p]) => p.operation_id !== MANIFEST_OPERATION_ID),
+          ),
+        );
+        invoke<RepoInfo[]>("get_all_repos").then(setRepos).catch(() => {});
+      }
+    },
+    [],
+  );
+
+  const cancelManifestSync = useCallback(async () => {
+    await invoke("cancel_operation", { operationId: MANIFEST_OPERATION_ID });
+  }, []);
+
+  const getUnpushedReport = useCallback(async () => {
+    return invoke<UnpushedReport>("get_unpushed_report");
+  }, []);
+
+  const exportUnpushedReport = useCallback(async (file: string, format: ReportFormat) => {
+    return invoke<UnpushedReport>("export_unpushed_report", { file, format });
+  }, []);
+
+  // Continue, skip or abort a merge/rebase/etc. the repo was left in
+  const resolveInProgress = useCallback(
+    async (path: string, action: InProgressAction) => {
+      const updated = await invoke<RepoInfo>(`${action}_in_progress`, { path });
       updateRepo(updated);
     },
     [updateRepo],
   );
 
-  const pushRepo = useCallback(
+  // Large ignored files aren't counted by scans; measure them on request
+  const measureIgnoredLargeFiles = useCallback(
     async (path: string) => {
-      const updated = await invoke<RepoInfo>("push_repo", { path });
+      const updated = await invoke<RepoInfo>("measure_ignored_large_files", { path });
       updateRepo(updated);
     },
     [updateRepo],
@@ -102,12 +318,31 @@ export function useRepos() {
     repos,
     loading,
     error,
+    bulkReport,
+    clearBulkReport: () => setBulkReport(null),
+    progress,
+    watching,
+    toggleWatching,
+    scanProgress,
     scanRepos,
+    cancelScan,
     refreshRepos,
+    queryRepos,
     fetchAll,
     pullAll,
+    cancelBulk,
     fetchRepo,
     pullRebaseRepo,
     pushRepo,
+    cancelRepoOperation,
+    cloneRepo,
+    cancelClone,
+    exportManifest,
+    syncManifest,
+    cancelManifestSync,
+    getUnpushedReport,
+    exportUnpushedReport,
+    resolveInProgress,
+    measureIgnoredLargeFiles,
   };
 }
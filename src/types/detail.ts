@@ -1,3 +1,5 @@
+import type { RepoInfo } from "./repo";
+
 export type RefKind = "head" | "local" | "remote" | "tag";
 
 export interface RefLabel {
@@ -52,6 +54,32 @@ export interface RemoteInfo {
   url: string;
 }
 
+export interface SubmoduleInfo {
+  name: string;
+  path: string;
+  url: string | null;
+  branch: string | null;
+  recorded_commit: string | null;
+  checked_out_commit: string | null;
+  initialized: boolean;
+  checked_out: boolean;
+  drifted: boolean;
+  dirty: boolean;
+  out_of_sync: boolean;
+}
+
+export interface WorktreeInfo {
+  /** Null for the main working tree. */
+  name: string | null;
+  path: string;
+  is_main: boolean;
+  locked: boolean;
+  lock_reason: string | null;
+  /** The working tree is gone; pruning removes what's left of it. */
+  prunable: boolean;
+  status: RepoInfo | null;
+}
+
 export interface GitProfile {
   name: string;
   email: string;
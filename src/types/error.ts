@@ -0,0 +1,24 @@
+export type ErrorCategory =
+  | "auth"
+  | "network"
+  | "conflict"
+  | "no_upstream"
+  | "no_remote"
+  | "detached_head"
+  | "not_found"
+  | "locked"
+  | "permission"
+  | "rejected"
+  | "cancelled"
+  | "database"
+  | "io"
+  | "other";
+
+/** Shape of every error returned by a Tauri command. */
+export interface AppError {
+  code: string;
+  category: ErrorCategory;
+  message: string;
+  repo_path: string | null;
+  hint: string | null;
+}
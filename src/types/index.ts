@@ -1,4 +1,39 @@
-export type { RepoInfo, RepoHealth } from "./repo";
+export type {
+  RepoInfo,
+  RepoQuery,
+  RepoSort,
+  RepoHealth,
+  RepoKind,
+  RepoFlag,
+  Severity,
+  HealthReason,
+  InProgressOperation,
+  InProgressAction,
+  StatusSnapshot,
+  RepoTrend,
+  CheckStatus,
+  DiagnosticCheck,
+  RepoDiagnostics,
+  OperationOutcome,
+  RepoOperationResult,
+  BulkSummary,
+  BulkReport,
+  BulkRepoEvent,
+  ManifestFormat,
+  ManifestOutcome,
+  ManifestEntryResult,
+  ManifestSyncReport,
+  UnpushedBranch,
+  UnpushedWork,
+  UnpushedReport,
+  ReportFormat,
+  ScanEvent,
+  ScanRoot,
+  NestedRepos,
+  ScanUpdate,
+  NetworkProgress,
+  GitProgressEvent,
+} from "./repo";
 export type {
   CommitInfo,
   RefLabel,
@@ -9,5 +44,8 @@ export type {
   StashEntry,
   CommitFileChange,
   RemoteInfo,
+  SubmoduleInfo,
+  WorktreeInfo,
   GitProfile,
 } from "./detail";
+export type { AppError, ErrorCategory } from "./error";
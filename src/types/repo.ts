@@ -1,4 +1,63 @@
-export type RepoHealth = "clean" | "dirty" | "diverged" | "error";
+import type { StashEntry } from "./detail";
+import type { ErrorCategory } from "./error";
+
+export type RepoHealth =
+  | "clean"
+  | "dirty"
+  | "diverged"
+  | "behind_trunk"
+  | "in_progress"
+  | "error";
+
+/** Boolean condition fields on RepoInfo that the dashboard can filter on. */
+export type RepoFlag = "detached" | "no_origin" | "no_upstream" | "upstream_gone" | "conflicted";
+
+export type Severity = "ok" | "info" | "warning" | "error";
+
+export interface HealthReason {
+  rule: string;
+  severity: Severity;
+  message: string;
+}
+
+export type InProgressOperation =
+  | "merge"
+  | "rebase"
+  | "cherry_pick"
+  | "revert"
+  | "bisect"
+  | "apply_mailbox";
+
+export type InProgressAction = "continue" | "skip" | "abort";
+
+/** Whether discovery looks for repos inside other repos' working trees. */
+export type NestedRepos = "skip" | "nested" | "vendored";
+
+/** A directory to scan, with its discovery settings (see `git/discovery.rs`). */
+export interface ScanRoot {
+  path: string;
+  max_depth: number | null;
+  include: string[];
+  exclude: string[];
+  follow_symlinks: boolean;
+  enabled: boolean;
+  nested: NestedRepos;
+}
+
+/** How a repo is laid out on disk (see `discovery::classify`). */
+export type RepoKind = "standard" | "worktree" | "submodule" | "separate_git_dir" | "bare";
+
+export type RepoSort = "name" | "last_commit" | "last_fetch" | "last_activity" | "last_checked";
+
+/** Sorting and filtering applied by the database; time bounds are RFC 3339. */
+export interface RepoQuery {
+  sort?: RepoSort;
+  descending?: boolean;
+  active_since?: string;
+  inactive_since?: string;
+  fetched_before?: string;
+  author?: string;
+}
 
 export interface RepoInfo {
   id: number | null;
@@ -12,4 +71,193 @@ export interface RepoInfo {
   health: RepoHealth;
   last_checked: string;
   remote_url: string | null;
+  staged_files: number;
+  unstaged_files: number;
+  untracked_files: number;
+  conflicted_files: number;
+  ignored_large_files: number;
+  upstream: string | null;
+  detached: boolean;
+  no_origin: boolean;
+  no_upstream: boolean;
+  upstream_gone: boolean;
+  conflicted: boolean;
+  default_branch: string | null;
+  ahead_default: number;
+  behind_default: number;
+  remote_count: number;
+  last_commit_at: string | null;
+  last_commit_author: string | null;
+  last_fetched_at: string | null;
+  last_activity_at: string | null;
+  branch_since: string | null;
+  severity: Severity;
+  health_reasons: HealthReason[];
+  in_progress: InProgressOperation | null;
+  error_detail: string | null;
+  kind: RepoKind;
+  /** The main repo of a worktree, or the superproject of a submodule. */
+  parent_path: string | null;
+  /** The repo whose working tree this one sits inside. */
+  nested_in: string | null;
+  submodule_count: number;
+  /** Submodules checked out at a different commit than the one recorded. */
+  submodules_drifted: number;
+  submodules_uninitialized: number;
+}
+
+export interface StatusSnapshot {
+  repo_path: string;
+  recorded_at: string;
+  branch: string;
+  ahead: number;
+  behind: number;
+  dirty_files: number;
+  stash_count: number;
+  health: RepoHealth;
+}
+
+export interface RepoTrend {
+  path: string;
+  name: string;
+  dirty_since: string | null;
+  dirty_days: number | null;
+  ahead_since: string | null;
+  ahead_days: number | null;
+  behind_since: string | null;
+  behind_days: number | null;
+  diverged_since: string | null;
+  diverged_days: number | null;
+  snapshot_count: number;
+}
+
+export type CheckStatus = "ok" | "warning" | "failed";
+
+export interface DiagnosticCheck {
+  name: string;
+  status: CheckStatus;
+  detail: string;
+}
+
+export interface RepoDiagnostics {
+  path: string;
+  healthy: boolean;
+  checks: DiagnosticCheck[];
 }
+
+export type OperationOutcome =
+  | { status: "success" }
+  | { status: "skipped"; reason: string }
+  | { status: "failed"; category: ErrorCategory; message: string };
+
+export interface RepoOperationResult {
+  path: string;
+  name: string;
+  outcome: OperationOutcome;
+  duration_ms: number;
+  repo: RepoInfo;
+}
+
+export interface BulkSummary {
+  total: number;
+  succeeded: number;
+  skipped: number;
+  failed: number;
+  failed_by_category: Partial<Record<ErrorCategory, number>>;
+}
+
+export interface BulkReport {
+  operation: "fetch" | "pull";
+  summary: BulkSummary;
+  results: RepoOperationResult[];
+}
+
+export type ManifestFormat = "native" | "repo_xml" | "url_list";
+
+export type ManifestOutcome =
+  | { status: "present" }
+  | { status: "cloned" }
+  | { status: "missing" }
+  | { status: "mismatched"; reasons: string[] }
+  | { status: "duplicate" }
+  | { status: "failed"; category: ErrorCategory; message: string };
+
+export interface ManifestEntryResult {
+  path: string;
+  url: string | null;
+  outcome: ManifestOutcome;
+}
+
+export interface ManifestSyncReport {
+  format: ManifestFormat;
+  root: string;
+  entries: ManifestEntryResult[];
+  extra: string[];
+}
+
+export interface UnpushedBranch {
+  name: string;
+  upstream: string | null;
+  upstream_gone: boolean;
+  unpushed_commits: number;
+  last_commit_at: string | null;
+}
+
+export interface UnpushedWork {
+  path: string;
+  name: string;
+  branches: UnpushedBranch[];
+  detached_commits: number;
+  stashes: StashEntry[];
+  changed_files: string[];
+  untracked_files: string[];
+  error: string | null;
+}
+
+export interface UnpushedReport {
+  generated_at: string;
+  repos_checked: number;
+  repos: UnpushedWork[];
+}
+
+export type ReportFormat = "markdown" | "json";
+
+export interface BulkRepoEvent {
+  operation: "fetch" | "pull";
+  result: RepoOperationResult;
+  completed: number;
+  total: number;
+}
+
+export type ScanUpdate =
+  | { kind: "discovered"; path: string }
+  | { kind: "completed"; repo: RepoInfo }
+  | { kind: "walking" };
+
+/** Emitted as `scan-event` while a scan runs, with running totals. */
+export type ScanEvent = ScanUpdate & {
+  operation_id: string | null;
+  dirs_visited: number;
+  discovered: number;
+  completed: number;
+};
+
+export type NetworkProgress =
+  | {
+      stage: "transfer";
+      remote: string;
+      received_objects: number;
+      indexed_objects: number;
+      local_objects: number;
+      total_objects: number;
+      indexed_deltas: number;
+      total_deltas: number;
+      received_bytes: number;
+    }
+  | { stage: "push"; remote: string; current: number; total: number; bytes: number }
+  | { stage: "remote"; remote: string; message: string };
+
+export type GitProgressEvent = NetworkProgress & {
+  operation_id: string | null;
+  path: string;
+};
@@ -0,0 +1,18 @@
+import type { AppError } from "../types";
+
+export function isAppError(err: unknown): err is AppError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    "code" in err &&
+    "message" in err
+  );
+}
+
+/** Human-readable text for an error thrown by `invoke`, including any fix hint. */
+export function errorMessage(err: unknown): string {
+  if (isAppError(err)) {
+    return err.hint ? `${err.message} — ${err.hint}` : err.message;
+  }
+  return String(err);
+}